@@ -17,4 +17,17 @@ grep -o \".*\" $1/drivers/devicelist.c | \
     sed -e 's%/\*%,%' -e 's%:%,%' -e s'%*/%%' | \
     sort > mspheaders.txt
 
-python3 mkphf.py mspdebug.txt mspheaders.txt
+(cd $2/include; grep "ORIGIN *=" -R --include="*.ld" .) | \
+    sed -e 's%./%%' -e 's/.ld:/,/' | \
+    tr [:lower:] [:upper:] | \
+    sed -e 's%/\*.*%%' -e 's%:%,%' | \
+    sort > mspmemory.txt
+(cd $2/include; grep -o -E "__MSP430_HAS_MSP430XV?2?_CPU__" *.h) | \
+    sed -e 's/.h:/,/' | \
+    tr [:lower:] [:upper:] | \
+    sort | \
+    uniq > mspcores.txt
+
+python3 mkphf.py mspdebug.txt mspheaders.txt > ../src/infomem.rs
+python3 mkphf.py --devices mspdebug.txt mspheaders.txt mspmemory.txt \
+    mspcores.txt > ../src/devicemap.rs
@@ -1,23 +1,28 @@
 import csv
+import itertools
 import re
 import sys
 
 origin_re = re.compile(r"\s*ORIGIN\s*=\s*(0X[0-9A-Fa-f]*)\s*")
 length_re = re.compile(r"\s*LENGTH\s*=\s*(0X[0-9A-Fa-f]*)\s*")
 sectors_re = re.compile(r"\s*SIZE\s*[0-9]*\s*AS\s*[0-9]*\s*([0-9]*)-BYTE\s*SEGMENTS\s*")  # noqa: E501
+region_re = re.compile(r"\s*([A-Z0-9_]+)[^,]*,\s*ORIGIN\s*=\s*(0X[0-9A-F]+)\s*,\s*LENGTH\s*=\s*(0X[0-9A-F]+)")  # noqa: E501
 
 
-def main():
+def read_debug_names(path):
     debug_names = set()
-    with open(sys.argv[1]) as fp:
+    with open(path) as fp:
         for msp in fp.read().splitlines():
             debug_names.add(msp)
 
+    return debug_names
+
+
+def read_infomem(path, debug_names):
     not_preset_in_debug = set()
-    used_debug = set()
     info = dict()
 
-    with open(sys.argv[2], newline='') as csvfile:
+    with open(path, newline='') as csvfile:
         mspreader = csv.reader(csvfile, delimiter=',')
         while True:
             try:
@@ -31,9 +36,19 @@ def main():
                 not_preset_in_debug.add(name)
             else:
                 info[name] = extract_info(origin, length, sectors)
-                used_debug.add(name)
 
-    not_present_in_headers = debug_names - used_debug
+    return info
+
+
+def main():
+    if sys.argv[1] == "--devices":
+        devices(*sys.argv[2:])
+        return
+
+    debug_names = read_debug_names(sys.argv[1])
+    info = read_infomem(sys.argv[2], debug_names)
+
+    not_present_in_headers = debug_names - set(info)
     manual_rules = dict()
 
     for n in not_present_in_headers:
@@ -67,6 +82,136 @@ pub(crate) static INFOMEM_MAP: Map<&'static str, Option<(u16, u16, u16)>> = phf_
     print("};")
 
 
+def devices(debug_path, infomem_path, memory_path, cores_path):
+    debug_names = read_debug_names(debug_path)
+    infomem = read_infomem(infomem_path, debug_names)
+    memory = read_memory(memory_path)
+    cores = read_cores(cores_path)
+    info = dict()
+
+    for n in debug_names & set(memory):
+        res = extract_device(memory[n], cores.get(n, "Cpu"), infomem.get(n))
+        if res:
+            info[n] = res
+
+    not_present_in_headers = debug_names - set(info)
+
+    print("""// Most parts have a single range of main memory.
+#![allow(clippy::single_range_in_vec_init)]
+""")
+    notes = read_notes(memory_path)
+    if notes:
+        print("\n".join(f"// {n}" for n in notes) + "\n")
+
+    print("""use phf::{phf_map, Map};
+
+use crate::device::{Core, DeviceInfo, InfoMem, Technology};
+
+/* Autogenerated by mkphf.py */
+pub(crate) static DEVICE_MAP: Map<&'static str, Option<DeviceInfo>> = phf_map! {""")  # noqa: E501
+    print("/* Extracted from mspmemory.txt and mspcores.txt. */")
+    for n, d in sorted(info.items()):
+        print(f"\"{n}\" => Some({format_device(d)}),")
+
+    print("/* Not in mspmemory.txt, or missing a required region. */")
+    for n in sorted(not_present_in_headers):
+        if n:
+            print(f"\"{n}\" => None,")
+
+    print("};")
+
+
+def read_notes(path):
+    # Leading comment lines say where the input came from, if not from TI's
+    # linker scripts; they are copied into the generated file.
+    with open(path) as fp:
+        return [line[1:].strip() for line in
+                itertools.takewhile(lambda line: line.startswith("#"),
+                                    fp.read().splitlines())]
+
+
+def read_memory(path):
+    memory = dict()
+
+    with open(path) as fp:
+        for line in fp.read().splitlines():
+            if line.startswith("#"):
+                continue
+            (name, _, region) = line.partition(",")
+            m = region_re.match(region)
+            if m:
+                memory.setdefault(name, dict())[m[1]] = \
+                    (int(m[2], base=16), int(m[3], base=16))
+
+    return memory
+
+
+def read_cores(path):
+    cores = dict()
+
+    with open(path) as fp:
+        for line in fp.read().splitlines():
+            (name, _, define) = line.partition(",")
+            cores[name] = "CpuXv2" if "XV2" in define else "CpuX"
+
+    return cores
+
+
+def span(regions, pred):
+    ranges = [(o, o + l) for (n, (o, l)) in regions.items() if pred(n)]
+    if not ranges:
+        return None
+
+    return (min(o for (o, _) in ranges), max(e for (_, e) in ranges))
+
+
+def extract_device(regions, core, infomem):
+    fram = "FRAM" in regions
+    main = [regions[n] for n in ("ROM", "HIROM", "FRAM", "HIFRAM")
+            if n in regions]
+    main = sorted((o, o + l) for (o, l) in main)
+    ram = span(regions, lambda n: n in ("RAM", "USBRAM"))
+    peripherals = span(regions,
+                       lambda n: n == "SFR" or n.startswith("PERIPHERAL"))
+    bsl = span(regions,
+               lambda n: n.startswith("BSL") and n != "BSLSIGNATURE")
+    vectors = span(regions, lambda n: n.startswith("VECT") or n == "RESETVEC")
+
+    if not (main and ram and peripherals and vectors):
+        return None
+
+    return {
+        "core": core,
+        "technology": "Fram" if fram else "Flash",
+        "main": main,
+        "ram": ram,
+        "peripherals": peripherals,
+        "bsl": bsl,
+        "infomem": infomem,
+        "vectors": vectors[0],
+    }
+
+
+def format_range(r):
+    return f"0x{r[0]:X}..0x{r[1]:X}"
+
+
+def format_device(d):
+    main = ", ".join(format_range(r) for r in d["main"])
+    bsl = f"Some({format_range(d['bsl'])})" if d["bsl"] else "None"
+    if d["infomem"]:
+        (o, l, s) = d["infomem"]
+        infomem = f"Some(InfoMem {{ origin: 0x{o:X}, length: 0x{l:X}, segment_size: {s} }})"  # noqa: E501
+    else:
+        infomem = "None"
+
+    return (f"DeviceInfo {{ core: Core::{d['core']}, "
+            f"technology: Technology::{d['technology']}, main: &[{main}], "
+            f"ram: {format_range(d['ram'])}, "
+            f"peripherals: {format_range(d['peripherals'])}, bsl: {bsl}, "
+            f"infomem: {infomem}, vectors: 0x{d['vectors']:X} }}")
+
+
 def extract_info(origin, length, sectors):
     origin = int(origin_re.match(origin)[1], base=16)
     length = int(length_re.match(length)[1], base=16)
@@ -0,0 +1,153 @@
+"""Rebuild the mkphf.py inputs without msp430-gcc-support-files.
+
+mspdebug.txt and mspheaders.txt are recovered from ../src/infomem.rs, which
+devicelist.sh generated from mspdebug's devicelist.c and TI's headers.
+mspmemory.txt and mspcores.txt are written from the per-family flash and RAM
+sizes below, taken from the datasheets, laid out the way TI's linker scripts
+lay out each family. Parts not listed here map to None.
+
+Run from this directory, then regenerate ../src/devicemap.rs with mkphf.py.
+devicelist.sh replaces all four files with ones extracted from TI's files.
+"""
+import re
+
+NOTES = """# Not extracted from TI's msp430-gcc-support-files: written by
+# scripts/reconstruct.py from datasheet flash and RAM sizes per family. Run
+# scripts/devicelist.sh against msp430-gcc-support-files to replace it.
+"""
+
+src = open("../src/infomem.rs").read()
+names = re.findall(r'^"([^"]+)" =>', src, re.M)
+open("mspdebug.txt", "w").write("\n".join(sorted(names)) + "\n")
+
+hdr = src.split("/* Manual override given. */")[0]
+with open("mspheaders.txt", "w") as fp:
+    for n, o, l, s in re.findall(r'^"([^"]+)" => Some\(\(0x([0-9A-F]+), 0x([0-9A-F]+), (\d+)\)\)', hdr, re.M):
+        o, l, s = int(o, 16), int(l, 16), int(s)
+        fp.write(f"{n}, ORIGIN = 0X{o:04X}, LENGTH = 0X{l:04X} , END=0X{o+l-1:04X}, SIZE {l} AS {l//s} {s}-BYTE SEGMENTS\n")
+
+mem = []
+cores = []
+K = 1024
+
+def region(n, name, o, l):
+    mem.append(f"{n},  {name:16} , ORIGIN = 0X{o:04X}, LENGTH = 0X{l:04X} ")
+
+def vectors(n, start):
+    for i, a in enumerate(range(start, 0xFFFE, 2)):
+        region(n, f"VECT{i + 1}", a, 2)
+    region(n, "RESETVEC", 0xFFFE, 2)
+
+def legacy(n, flash, ram, vect=0xFFE0, bsl=True, cpux=False):
+    """1xx, 2xx and 4xx: RAM at 0x200, or 0x1100 past 2 KiB."""
+    region(n, "SFR", 0, 0x10)
+    region(n, "PERIPHERAL_8BIT", 0x10, 0xF0)
+    region(n, "PERIPHERAL_16BIT", 0x100, 0x100)
+    ram_o = 0x200 if ram <= 2 * K else 0x1100
+    region(n, "RAM", ram_o, ram)
+    if bsl:
+        region(n, "BSL", 0xC00, 0x400)
+    low = max(0x10000 - flash, 0x1100 if ram_o == 0x200 else ram_o + ram)
+    region(n, "ROM (RX)", low, vect - low)
+    if flash > 64 * K:
+        region(n, "HIROM (RX)", 0x10000, 0x10000)
+    vectors(n, vect)
+    if cpux:
+        cores.append(f"{n},__MSP430_HAS_MSP430X_CPU__")
+
+def xv2(n, main, ram, fram=False, low=None):
+    """5xx, 6xx and FRAM: RAM at 0x1c00, main memory up to the vectors."""
+    region(n, "SFR", 0, 0x10)
+    region(n, "PERIPHERAL_8BIT", 0x10, 0xF0)
+    region(n, "PERIPHERAL_16BIT", 0x100, 0xF00)
+    region(n, "BSL", 0x1000, 0x800)
+    region(n, "RAM", 0x1C00, ram)
+    low = low or max(0x10000 - main, 0x1C00 + ram)
+    region(n, "FRAM (RXW)" if fram else "ROM (RX)", low, 0xFF80 - low)
+    hi = main - (0x10000 - low)
+    if hi > 0:
+        region(n, "HIFRAM (RXW)" if fram else "HIROM (RX)", 0x10000, hi)
+    vectors(n, 0xFF80)
+    cores.append(f"{n},__MSP430_HAS_MSP430XV2_CPU__")
+
+# CC430: last digit 3/5/6/7 is 8K/16K/32K/32K flash.
+for n in ["CC430F5123", "CC430F5125", "CC430F5133", "CC430F5135", "CC430F5137",
+          "CC430F5143", "CC430F5145", "CC430F5147", "CC430F6125", "CC430F6126",
+          "CC430F6127", "CC430F6135", "CC430F6137", "CC430F6143", "CC430F6145",
+          "CC430F6147"]:
+    flash, ram = {"3": (8, 2), "5": (16, 2), "6": (32, 2), "7": (32, 4)}[n[-1]]
+    xv2(n, flash * K, ram * K)
+
+for n in ["MSP430AFE221", "MSP430AFE222", "MSP430AFE223", "MSP430AFE231",
+          "MSP430AFE232", "MSP430AFE233", "MSP430AFE251", "MSP430AFE252",
+          "MSP430AFE253"]:
+    flash, ram = {"1": (4, 256), "2": (8, 512), "3": (12, 512)}[n[-1]]
+    legacy(n, flash * K, ram)
+
+for n, flash, ram in [("F133", 8, 256), ("F135", 16, 512), ("F147", 32, 1024),
+                      ("F148", 48, 2048), ("F149", 60, 2048), ("F155", 16, 512),
+                      ("F156", 24, 1024), ("F157", 32, 1024), ("F167", 32, 1024),
+                      ("F168", 48, 2048), ("F169", 60, 2048), ("F1610", 32, 5120),
+                      ("F1611", 48, 10240), ("F1612", 55, 5120)]:
+    legacy("MSP430" + n, flash * K, ram)
+
+for n, flash, ram in [("F2112", 2, 256), ("F2122", 4, 512), ("F2132", 8, 512),
+                      ("F2232", 8, 512), ("F2234", 8, 512), ("F2252", 16, 512),
+                      ("F2254", 16, 512), ("F2272", 32, 1024), ("F2274", 32, 1024),
+                      ("F233", 8, 1024), ("F235", 16, 2048)]:
+    legacy("MSP430" + n, flash * K, ram)
+
+for n, flash, ram in [("F2330", 8, 1024), ("F2350", 16, 2048), ("F2370", 32, 2048),
+                      ("F247", 32, 4096), ("F2471", 32, 4096), ("F248", 48, 4096),
+                      ("F2481", 48, 4096), ("F249", 60, 2048), ("F2491", 60, 2048),
+                      ("G2955", 56, 4096)]:
+    legacy("MSP430" + n, flash * K, ram, vect=0xFFC0, bsl=n != "G2955")
+
+for n, flash, ram in [("F2410", 56, 4096), ("F2418", 116, 8192), ("F2419", 120, 4096),
+                      ("F2618", 116, 8192), ("F2619", 120, 4096)]:
+    legacy("MSP430" + n, flash * K, ram, vect=0xFFC0, cpux=True)
+
+for n, flash, ram in [("F423", 8, 256), ("F423A", 8, 256), ("F425", 16, 512),
+                      ("F425A", 16, 512), ("F427", 32, 1024), ("F427A", 32, 1024),
+                      ("F4250", 16, 256), ("FG4250", 16, 256), ("FE423", 8, 256),
+                      ("FE423A", 8, 256), ("FE4232", 8, 256), ("FE425", 16, 512),
+                      ("FE425A", 16, 512), ("FE427", 32, 1024), ("FE427A", 32, 1024),
+                      ("FE4272", 32, 1024), ("FW429", 60, 2048)]:
+    legacy("MSP430" + n, flash * K, ram)
+
+for n, flash, ram in [("F477", 32, 2048), ("F478", 48, 2048), ("F479", 60, 2048),
+                      ("FG477", 32, 2048), ("FG478", 48, 2048), ("FG479", 60, 2048)]:
+    legacy("MSP430" + n, flash * K, ram, vect=0xFFC0)
+
+for n, flash, ram in [("FG4618", 116, 8192), ("FG4619", 120, 4096)]:
+    legacy("MSP430" + n, flash * K, ram, vect=0xFFC0, cpux=True)
+
+for n, flash, ram in [("F5131", 8, 1), ("F5132", 8, 1), ("F5151", 16, 2),
+                      ("F5152", 16, 2), ("F5171", 32, 2), ("F5172", 32, 2),
+                      ("F5212", 64, 8), ("F5213", 96, 8), ("F5214", 128, 8),
+                      ("F5217", 64, 8), ("F5218", 96, 8), ("F5219", 128, 8),
+                      ("F5222", 64, 8), ("F5223", 96, 8), ("F5224", 128, 8),
+                      ("F5227", 64, 8), ("F5228", 96, 8), ("F5229", 128, 8),
+                      ("F5304", 8, 6), ("F5308", 16, 6), ("F5309", 24, 6),
+                      ("F5310", 32, 6),
+                      ("F5418", 128, 16), ("F5418A", 128, 16), ("F5419", 128, 16),
+                      ("F5419A", 128, 16), ("F5435", 192, 16), ("F5435A", 192, 16),
+                      ("F5436", 192, 16), ("F5436A", 192, 16), ("F5437", 256, 16),
+                      ("F5437A", 256, 16), ("F5438", 256, 16), ("F5438A", 256, 16),
+                      ("F5500", 8, 6), ("F5501", 16, 6), ("F5502", 24, 6),
+                      ("F5503", 32, 6), ("F5504", 8, 6), ("F5505", 16, 6),
+                      ("F5506", 24, 6), ("F5507", 32, 6), ("F5508", 16, 6),
+                      ("F5509", 24, 6), ("F5510", 32, 6),
+                      ("F5513", 32, 6), ("F5514", 64, 6), ("F5515", 64, 6),
+                      ("F5517", 96, 8), ("F5519", 128, 10), ("F5524", 64, 6),
+                      ("F5525", 64, 6), ("F5526", 96, 8), ("F5527", 96, 8),
+                      ("F5528", 128, 10), ("F5529", 128, 10)]:
+    xv2("MSP430" + n, flash * K, ram * K)
+
+for n, fram, ram, low in [("FR5729", 15.5, 1, 0xC200), ("FR5739", 15.5, 1, 0xC200),
+                          ("FR5949", 63, 2, 0x4400), ("FR5959", 63, 2, 0x4400),
+                          ("FR5969", 63, 2, 0x4400), ("FR5994", 256, 4, 0x4000)]:
+    xv2("MSP430" + n, int(fram * K), ram * K, fram=True, low=low)
+
+open("mspmemory.txt", "w").write(NOTES + "\n".join(sorted(mem)) + "\n")
+open("mspcores.txt", "w").write("\n".join(sorted(cores)) + "\n")
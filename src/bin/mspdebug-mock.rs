@@ -0,0 +1,23 @@
+use std::io;
+use std::process::ExitCode;
+
+use mspdebug_embedded::mock::{Transcript, TRANSCRIPT_VAR};
+
+/// Stand-in for `mspdebug --embedded` that replays a transcript; see
+/// `mspdebug_embedded::mock`.
+fn main() -> ExitCode {
+    let Some(path) = std::env::var_os(TRANSCRIPT_VAR) else {
+        eprintln!("mspdebug-mock: {} is not set", TRANSCRIPT_VAR);
+        return ExitCode::FAILURE;
+    };
+
+    let res = Transcript::load(&path).and_then(|t| t.play(io::stdin().lock(), io::stdout().lock()));
+
+    match res {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("mspdebug-mock: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
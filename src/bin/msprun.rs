@@ -1,25 +1,47 @@
+use std::fs::File;
+use std::io::{self, BufWriter};
 use std::path::PathBuf;
+use std::time::Duration;
 
 use clap::{Parser, Subcommand};
-use eyre::Result;
+use eyre::{eyre, Result};
 use mspdebug_embedded::*;
 
 #[derive(clap::Parser)]
 #[clap(name = "msprun", author, version)]
 /// `cargo run`-friendly driver program for `mspdebug`.
 pub struct Args {
-    /// Driver argument to pass to `mspdebug`
-    pub driver: TargetDriver,
+    /// Driver argument to pass to `mspdebug` (not needed for `list`)
+    pub driver: Option<TargetDriver>,
     /// High-level command to run (converted to multiple `mspdebug` commands)
     #[clap(subcommand)]
     pub cmd: Cmd,
     /// Explicit path to `mspdebug` binary (default to PATH)
     #[arg(short = 'b')]
     pub binary: Option<PathBuf>,
+    /// Open the USB programmer with this serial number
+    #[arg(short = 's')]
+    pub serial: Option<String>,
+    /// Open the programmer through this device path (tty, parallel port, ...)
+    #[arg(short = 'd')]
+    pub device_path: Option<PathBuf>,
+    /// Use JTAG instead of Spy-Bi-Wire
+    #[arg(short = 'j')]
+    pub jtag: bool,
+    /// Keep Information Memory segment A (calibration data) intact across
+    /// erases, backing it up to this directory (requires -s)
+    #[arg(short = 'a', requires = "serial")]
+    pub calibration_dir: Option<PathBuf>,
 }
 
 #[derive(Subcommand)]
 pub enum Cmd {
+    /** List USB devices and the `mspdebug` driver for each known programmer.
+
+    Runs `mspdebug --usb-list`. The serial number shown can be passed to `-s`
+    to pick one of several identical programmers.
+    */
+    List,
     /** Program attached msp430 microcontroller with given ELF file.
 
     This command is a wrapper over the `prog` command that is more friendly
@@ -30,7 +52,15 @@ pub enum Cmd {
     necessary. Information Memory segment A is untouched due to possibly
     containing calibration info.
     */
-    Prog { filename: PathBuf },
+    Prog {
+        filename: PathBuf,
+        /// Read back the programmed ELF file and compare it to the file.
+        #[arg(short = 'v')]
+        verify: bool,
+        /// Program the ELF file even if it doesn't fit the device.
+        #[arg(long)]
+        allow_misfit: bool,
+    },
     /** Use `mspdebug` to create a `gdb` server; spawn an interactive
     `msp430-elf-gdb` session.
 
@@ -63,20 +93,93 @@ pub enum Cmd {
         #[arg(short = 'e')]
         gdb_init: Vec<String>,
     },
+    /** Print the factory calibration of the attached msp430 microcontroller.
+
+    Decodes Information Memory segment A on 2xx parts, and the device
+    descriptor table on 5xx, 6xx and FRAM parts: DCO, ADC and reference
+    calibration, and the die record. Fails if the table's checksum doesn't
+    match.
+    */
+    Info,
+    /** Run the attached msp430 microcontroller and record its current draw.
+
+    Needs a driver and device that support power profiling (`tilib` or
+    `ezfet` with EnergyTrace). The target runs whatever is programmed; it is
+    halted again once the recording ends. Samples are written as CSV with
+    columns `time_us,mab,current_ua,energy_uj`.
+    */
+    Power {
+        /// Record for this many seconds.
+        seconds: f64,
+        /// Write the CSV to this file instead of stdout.
+        #[arg(short = 'o')]
+        output: Option<PathBuf>,
+    },
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
 
-    let mut cfg = Cfg::new();
+    let mut cfg = Cfg::new().jtag(args.jtag);
     if let Some(b) = args.binary {
         cfg = cfg.binary(b);
     }
+    if let Some(s) = args.serial {
+        cfg = cfg.serial(s);
+    }
+    if let Some(d) = args.device_path {
+        cfg = cfg.device_path(d);
+    }
+    if let Some(d) = args.calibration_dir {
+        cfg = cfg.calibration_backups(d);
+    }
+
+    let driver = || {
+        args.driver
+            .ok_or_else(|| eyre!("this command needs a driver"))
+    };
 
     match args.cmd {
-        Cmd::Prog { filename } => {
-            let mut msp = cfg.driver(args.driver).run()?;
-            msp.program(filename)?;
+        Cmd::List => {
+            for dev in cfg.list_devices()? {
+                let driver = dev.driver.map(|d| d.as_ref().to_owned());
+
+                print!(
+                    "{:03}:{:03} {:04x}:{:04x} {:<14} {}",
+                    dev.bus,
+                    dev.device,
+                    dev.vid,
+                    dev.pid,
+                    driver.as_deref().unwrap_or("-"),
+                    dev.description
+                );
+                match dev.serial {
+                    Some(s) => println!(" [serial: {}]", s),
+                    None => println!(),
+                }
+            }
+        }
+        Cmd::Prog {
+            filename,
+            verify,
+            allow_misfit,
+        } => {
+            let mut msp = cfg.driver(driver()?).run()?;
+            msp.on_diagnostic(|d| {
+                if d.severity == Severity::Warning {
+                    eprintln!("warning: {}", d.message);
+                }
+            });
+
+            let mut opts = ProgramOptions::default();
+            if verify {
+                opts = opts.verify();
+            }
+            if allow_misfit {
+                opts = opts.allow_misfit();
+            }
+
+            msp.program_with(filename, opts)?;
         }
         Cmd::Gdb {
             filename,
@@ -85,7 +188,7 @@ fn main() -> Result<()> {
             gdb_init,
             ..
         } => {
-            let msp = cfg.driver(args.driver).group(true).run()?;
+            let msp = cfg.driver(driver()?).group(true).run()?;
 
             let gdb = if reset_only {
                 GdbCfg::default().set_port(port).extra_cmds(gdb_init)
@@ -98,7 +201,91 @@ fn main() -> Result<()> {
 
             msp.gdb(filename, gdb)?;
         }
+        Cmd::Info => {
+            let mut msp = cfg.driver(driver()?).run()?;
+            let cal = msp.calibration()?;
+
+            println!("device: {}", msp.device().unwrap_or("unknown"));
+            if let Some(id) = cal.device_id {
+                println!("device id: 0x{:04x}", id);
+            }
+            if let (Some(hw), Some(fw)) = (cal.hw_revision, cal.fw_revision) {
+                println!("revision: hardware {}, firmware {}", hw, fw);
+            }
+            if let Some(die) = cal.die {
+                println!(
+                    "die: lot/wafer 0x{:08x}, x {}, y {}, test results 0x{:04x}",
+                    die.lot_wafer_id, die.die_x, die.die_y, die.test_results
+                );
+            }
+            for dco in &cal.dco {
+                println!(
+                    "dco {} MHz: CALDCO 0x{:02x}, CALBC1 0x{:02x}",
+                    dco.mhz, dco.caldco, dco.calbc1
+                );
+            }
+            if let Some(adc) = &cal.adc {
+                println!(
+                    "adc: gain factor 0x{:04x}, offset {}",
+                    adc.gain_factor, adc.offset
+                );
+                for t in &adc.temp_sensor {
+                    println!(
+                        "temperature sensor ({} mV reference): 30 C {}, 85 C {}",
+                        t.ref_mv, t.t30, t.t85
+                    );
+                }
+            }
+            for r in &cal.reference {
+                println!("reference {} mV: factor 0x{:04x}", r.ref_mv, r.factor);
+            }
+        }
+        Cmd::Power { seconds, output } => {
+            let duration = Duration::try_from_secs_f64(seconds)?;
+            let mut msp = cfg.driver(driver()?).run()?;
+            let profile = msp.profile_power(duration)?;
+
+            match output {
+                Some(path) => profile.write_csv(BufWriter::new(File::create(path)?))?,
+                None => profile.write_csv(io::stdout().lock())?,
+            }
+
+            eprintln!(
+                "{} samples over {:?}: {:.1} uA average, {:.1} uJ",
+                profile.samples().len(),
+                profile.duration(),
+                profile.average_current_ua(),
+                profile.energy_uj()
+            );
+        }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::ValueEnum;
+
+    #[test]
+    fn test_listed_driver_parses() {
+        // `list` prints the mspdebug name of each driver; it has to be
+        // accepted back as the driver argument.
+        for &d in TargetDriver::value_variants() {
+            let args = Args::try_parse_from(["msprun", d.as_ref(), "list"]).unwrap();
+            assert_eq!(args.driver, Some(d));
+        }
+    }
+
+    #[test]
+    fn test_calibration_dir_requires_serial() {
+        let args = ["msprun", "rf2500", "-a", "cal", "prog", "fw.elf"];
+        assert!(Args::try_parse_from(args).is_err());
+
+        let args = [
+            "msprun", "rf2500", "-a", "cal", "-s", "1234", "prog", "fw.elf",
+        ];
+        assert!(Args::try_parse_from(args).is_ok());
+    }
+}
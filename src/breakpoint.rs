@@ -0,0 +1,150 @@
+use std::path::Path;
+
+use crate::error::BadInputReason;
+use crate::image::open_elf;
+
+use super::{Error, Registers};
+#[cfg(doc)]
+use crate::MspDebug;
+
+/// What a [`Breakpoint`] triggers on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakpointKind {
+    /// Instruction fetch from the address (`setbreak`).
+    Break,
+    /// Any access to the address (`setwatch`).
+    Watch,
+    /// Data read from the address (`setwatchr`).
+    Read,
+    /// Data write to the address (`setwatchw`).
+    Write,
+}
+
+impl BreakpointKind {
+    pub(crate) fn command(&self) -> &'static str {
+        match self {
+            BreakpointKind::Break => "setbreak",
+            BreakpointKind::Watch => "setwatch",
+            BreakpointKind::Read => "setwatchr",
+            BreakpointKind::Write => "setwatchw",
+        }
+    }
+}
+
+/** A breakpoint slot in use, as listed by `break`.
+
+Whether a slot is backed by the device's hardware breakpoint unit or
+implemented in software is decided by the `mspdebug` driver; the number of
+slots reported by `break` is the driver's limit.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Breakpoint {
+    pub index: usize,
+    pub addr: u32,
+    /// Nearest symbol, if `mspdebug` has symbols loaded (e.g. after `prog`).
+    pub symbol: Option<String>,
+    pub kind: BreakpointKind,
+}
+
+impl Breakpoint {
+    /** Parse the table printed by `break`.
+
+    ```text
+    4 breakpoints available:
+        0. 0x0c000 (main)
+        1. 0x00200, write
+    ```
+    */
+    pub(crate) fn parse_table(lines: &[String]) -> Result<Vec<Breakpoint>, Error> {
+        let mut bps = Vec::new();
+
+        for line in lines {
+            let Some((index, rest)) = line.trim().split_once(". ") else {
+                continue;
+            };
+            let Ok(index) = index.parse() else {
+                continue;
+            };
+
+            let (desc, kind) = match rest.rsplit_once(", ") {
+                Some((d, "watch")) => (d, BreakpointKind::Watch),
+                Some((d, "read")) => (d, BreakpointKind::Read),
+                Some((d, "write")) => (d, BreakpointKind::Write),
+                _ => (rest, BreakpointKind::Break),
+            };
+
+            let mut addr = None;
+            let mut symbol = None;
+            for tok in desc
+                .split_whitespace()
+                .map(|t| t.trim_matches(|c| c == '(' || c == ')'))
+            {
+                match tok
+                    .strip_prefix("0x")
+                    .and_then(|h| u32::from_str_radix(h, 16).ok())
+                {
+                    Some(a) if addr.is_none() => addr = Some(a),
+                    _ => symbol = Some(tok.to_owned()),
+                }
+            }
+
+            bps.push(Breakpoint {
+                index,
+                addr: addr.ok_or_else(|| Error::UnexpectedOutput(line.clone()))?,
+                symbol,
+                kind,
+            });
+        }
+
+        Ok(bps)
+    }
+}
+
+/// Why a [`Running`](crate::Running) target stopped, and where.
+#[derive(Clone, Debug)]
+pub struct Stop {
+    pub regs: Registers,
+    /// The breakpoint at the stopped PC, if any.
+    pub breakpoint: Option<Breakpoint>,
+}
+
+impl Stop {
+    /// Stopped with `regs`; `bps` are the breakpoints currently set.
+    pub(crate) fn at(regs: Registers, bps: Vec<Breakpoint>) -> Self {
+        let breakpoint = bps
+            .into_iter()
+            .find(|b| b.kind == BreakpointKind::Break && b.addr == regs.pc());
+
+        Self { regs, breakpoint }
+    }
+}
+
+/** Look up the address of `name` in the symbol table of an ELF file.
+
+Returns `Ok(None)` if the file has no symbol table or no such symbol. Useful
+together with [`MspDebug::run_until`] for "run until `main`" style tests.
+*/
+pub fn symbol_address<F>(filename: F, name: &str) -> Result<Option<u32>, Error>
+where
+    F: AsRef<Path>,
+{
+    let mut elf = open_elf(filename)?;
+    let Some((symtab, strtab)) = elf
+        .symbol_table()
+        .map_err(|p| Error::BadInput(BadInputReason::ElfParseError(p)))?
+    else {
+        return Ok(None);
+    };
+
+    for sym in symtab.iter() {
+        let sym_name = strtab
+            .get(sym.st_name as usize)
+            .map_err(|p| Error::BadInput(BadInputReason::ElfParseError(p)))?;
+
+        if sym_name == name {
+            return Ok(Some(sym.st_value as u32));
+        }
+    }
+
+    Ok(None)
+}
@@ -0,0 +1,181 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::driver::infomem_layout;
+use crate::protocol::parse_hexdump_line;
+use crate::tlv::{check_segment_a_checksum, table_location, TableKind};
+use crate::{Connection, Error, TargetDriver};
+
+/** Keeps Information Memory segment A intact across erases, see
+[`Cfg::protect_calibration`](crate::Cfg::protect_calibration).
+
+Segment A holds the factory DCO and ADC calibration on most devices. Before
+an erase, the session reads it back, and writes it again afterwards if the
+erase clobbered it.
+*/
+#[derive(Clone, Debug)]
+pub(crate) struct Safeguard {
+    dir: Option<PathBuf>,
+    serial: Option<String>,
+}
+
+impl Safeguard {
+    pub(crate) fn new(dir: Option<PathBuf>, serial: Option<String>) -> Self {
+        Self { dir, serial }
+    }
+
+    /// Backup file for `device`, if backups are enabled: one per device and
+    /// programmer serial number. Without a serial number, there is no telling
+    /// boards of the same family apart, so nothing is backed up.
+    pub(crate) fn backup_path(&self, device: &str) -> Option<PathBuf> {
+        let dir = self.dir.as_ref()?;
+        let serial = self.serial.as_deref()?;
+
+        Some(dir.join(format!("{}-{}.infoa", device, serial)))
+    }
+
+    /** Back up segment A of `device`, read from `addr`.
+
+    An erased segment is not saved, so that a good backup isn't replaced by a
+    board that has already lost its calibration.
+    */
+    pub(crate) fn save(&self, device: &str, addr: u32, data: &[u8]) -> Result<(), Error> {
+        match self.backup_path(device) {
+            Some(path) if !is_erased(data) => {
+                save_backup(&path, addr, data).map_err(Error::CalibrationBackupError)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Address and length of Information Memory segment A, the last segment.
+/// Simulators have no calibration to protect.
+pub(crate) fn segment_a(
+    driver: TargetDriver,
+    device: Option<&str>,
+) -> Result<Option<(u32, usize)>, Error> {
+    if driver.capabilities().connection == Connection::Simulator {
+        return Ok(None);
+    }
+
+    let (origin, length, sector_size) = infomem_layout(device)?;
+    let addr = u32::from(origin) + u32::from(length - sector_size);
+
+    Ok(Some((addr, sector_size.into())))
+}
+
+pub(crate) fn check_segment_a(addr: u32, len: usize, data: &[u8]) -> Result<(), Error> {
+    if data.len() != len {
+        return Err(Error::MemoryWriteError {
+            addr,
+            msg: format!("segment A is {} bytes, got {}", len, data.len()),
+        });
+    }
+
+    Ok(())
+}
+
+/** Load the segment A backup for `device`, checking that it belongs at
+`addr`.
+
+Backups are only restored to the board they were taken from, so a programmer
+serial number is required. If segment A holds the calibration table, its
+checksum is checked too.
+*/
+pub(crate) fn read_segment_a_backup(
+    safeguard: Option<Safeguard>,
+    device: Option<&str>,
+    addr: u32,
+) -> Result<Vec<u8>, Error> {
+    let safeguard = safeguard
+        .filter(|s| s.dir.is_some())
+        .ok_or_else(|| not_found("no calibration backup directory configured"))?;
+    let path = safeguard
+        .backup_path(device.unwrap_or_default())
+        .ok_or_else(|| not_found("no programmer serial number configured to pick a backup"))?;
+
+    let (backup_addr, data) = load_backup(&path)?;
+    if backup_addr != addr {
+        return Err(Error::UnexpectedOutput(format!(
+            "{} is for address 0x{:x}, expected 0x{:x}",
+            path.display(),
+            backup_addr,
+            addr
+        )));
+    }
+
+    if let Ok((table_addr, len, TableKind::SegmentA)) = table_location(device) {
+        if table_addr == addr && data.len() == len {
+            check_segment_a_checksum(addr, &data)?;
+        }
+    }
+
+    Ok(data)
+}
+
+fn not_found(msg: &str) -> Error {
+    Error::CalibrationBackupError(io::Error::new(io::ErrorKind::NotFound, msg))
+}
+
+/// Flash reads back as all ones after an erase.
+pub(crate) fn is_erased(data: &[u8]) -> bool {
+    data.iter().all(|&b| b == 0xff)
+}
+
+/** Write a backup as an `mspdebug`-style hex dump:
+
+```text
+    010c0: 86 97 ff ff ff ff ff ff ff ff ff ff ff ff ff ff
+```
+*/
+fn save_backup(path: &Path, addr: u32, data: &[u8]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut text = String::new();
+    for (i, line) in data.chunks(16).enumerate() {
+        let _ = write!(text, "    {:05x}:", addr as usize + i * 16);
+        for b in line {
+            let _ = write!(text, " {:02x}", b);
+        }
+        text.push('\n');
+    }
+
+    fs::write(path, text)
+}
+
+/// Read a backup written by [`Safeguard::save`] and return its address and
+/// data.
+fn load_backup(path: &Path) -> Result<(u32, Vec<u8>), Error> {
+    let text = fs::read_to_string(path).map_err(Error::CalibrationBackupError)?;
+    let mut lines = text
+        .lines()
+        .map(|l| parse_hexdump_line(l).ok_or_else(|| Error::UnexpectedOutput(l.to_owned())));
+
+    let (addr, mut data) = lines.next().unwrap_or_else(|| {
+        Err(Error::UnexpectedOutput(format!(
+            "{} is empty",
+            path.display()
+        )))
+    })?;
+
+    for line in lines {
+        let (line_addr, bytes) = line?;
+        if line_addr != addr + data.len() as u32 {
+            return Err(Error::UnexpectedOutput(format!(
+                "{}: expected address 0x{:x}, got 0x{:x}",
+                path.display(),
+                addr + data.len() as u32,
+                line_addr
+            )));
+        }
+
+        data.extend(bytes);
+    }
+
+    Ok((addr, data))
+}
@@ -1,19 +1,33 @@
+use std::any;
 use std::convert::AsRef;
+use std::ffi::OsString;
+use std::net::{TcpStream, ToSocketAddrs};
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
+use std::time::Duration;
 
 use command_group::CommandGroup;
 
 #[cfg(windows)]
 use winapi::um::winbase::CREATE_NEW_PROCESS_GROUP;
 
+use bitflags::bitflags;
 #[cfg(feature = "msprun")]
 use clap::ValueEnum;
 use strum_macros::AsRefStr;
 
-use super::{Error, MspDebug};
+use super::calibration::Safeguard;
+use super::driver::StderrCallback;
+#[cfg(feature = "mock")]
+use super::mock::{Replay, Transcript};
+use super::power::DEFAULT_MILLIVOLTS;
+use super::transcript::Recorder;
+use super::transport::{Process, ProcessTransport};
+use super::{Error, MspDebug, Transport};
 
-#[derive(Clone, Copy, AsRefStr, PartialEq)]
+/// An `mspdebug` driver. [`AsRef<str>`] gives the name `mspdebug` (and
+/// `msprun`) takes on the command line.
+#[derive(Clone, Copy, Debug, AsRefStr, PartialEq, Eq)]
 #[cfg_attr(feature = "msprun", derive(ValueEnum))]
 pub enum TargetDriver {
     /// eZ430-RF2500 devices (USB)
@@ -24,6 +38,7 @@ pub enum TargetDriver {
     Olimex,
     /// Olimex MSP-JTAG-TINY (V1)
     #[strum(serialize = "olimex-v1")]
+    #[cfg_attr(feature = "msprun", value(name = "olimex-v1"))]
     Olimexv1,
     /// Olimex MSP-JTAG-ISO
     #[strum(serialize = "olimex-iso")]
@@ -36,6 +51,7 @@ pub enum TargetDriver {
     Sim,
     /// CPUX Simulation mode
     #[strum(serialize = "simx")]
+    #[cfg_attr(feature = "msprun", value(name = "simx"))]
     SimX,
     /// TI FET430UIF and compatible devices (e.g. eZ430)
     #[strum(serialize = "uif")]
@@ -48,12 +64,14 @@ pub enum TargetDriver {
     FlashBsl,
     /// GDB client mode
     #[strum(serialize = "gdbc")]
+    #[cfg_attr(feature = "msprun", value(name = "gdbc"))]
     GdbClient,
     /// TI MSP430 library
     #[strum(serialize = "tilib")]
     Tilib,
     /// GoodFET MSP430 JTAG
     #[strum(serialize = "goodfet")]
+    #[cfg_attr(feature = "msprun", value(name = "goodfet"))]
     GoodFet,
     /// Parallel Port JTAG
     #[strum(serialize = "pif")]
@@ -66,6 +84,7 @@ pub enum TargetDriver {
     LoadBsl,
     /// Texas Instruments eZ-FET
     #[strum(serialize = "ezfet")]
+    #[cfg_attr(feature = "msprun", value(name = "ezfet"))]
     EzFet,
     /// ROM bootstrap loader
     #[strum(serialize = "rom-bsl")]
@@ -75,14 +94,196 @@ pub enum TargetDriver {
     BusPirate,
     /// MehFET USB JTAG/SBW device
     #[strum(serialize = "mehfet")]
+    #[cfg_attr(feature = "msprun", value(name = "mehfet"))]
     MehFet,
 }
 
+bitflags! {
+    /// `mspdebug` command-line options, as set through [`Cfg`]'s builders.
+    pub struct DriverOptions: u32 {
+        const JTAG = 1;
+        const DEVICE_PATH = 1 << 1;
+        const USB_DEVICE = 1 << 2;
+        const SERIAL = 1 << 3;
+        const VOLTAGE = 1 << 4;
+        const FET_FORCE_ID = 1 << 5;
+        const FET_SKIP_CLOSE = 1 << 6;
+        const USB_LIST = 1 << 7;
+        const FORCE_RESET = 1 << 8;
+        const ALLOW_FW_UPDATE = 1 << 9;
+        const REQUIRE_FW_UPDATE = 1 << 10;
+        const LONG_PASSWORD = 1 << 11;
+        const BSL_ENTRY_SEQUENCE = 1 << 12;
+
+        const USB = Self::USB_DEVICE.bits | Self::SERIAL.bits;
+        const FET = Self::VOLTAGE.bits
+            | Self::FET_FORCE_ID.bits
+            | Self::FET_SKIP_CLOSE.bits
+            | Self::FORCE_RESET.bits
+            | Self::REQUIRE_FW_UPDATE.bits;
+    }
+}
+
+impl DriverOptions {
+    /// Each single option, in the order they are passed to `mspdebug`.
+    fn each() -> impl Iterator<Item = DriverOptions> {
+        (0..=12).map(|b| DriverOptions::from_bits_truncate(1 << b))
+    }
+
+    /// Command-line spelling of a single option, as yielded by `each`.
+    fn flag(&self) -> &'static str {
+        match *self {
+            DriverOptions::JTAG => "-j",
+            DriverOptions::DEVICE_PATH => "-d",
+            DriverOptions::USB_DEVICE => "-U",
+            DriverOptions::SERIAL => "-s",
+            DriverOptions::VOLTAGE => "-v",
+            DriverOptions::FET_FORCE_ID => "--fet-force-id",
+            DriverOptions::FET_SKIP_CLOSE => "--fet-skip-close",
+            DriverOptions::USB_LIST => "--usb-list",
+            DriverOptions::FORCE_RESET => "--force-reset",
+            DriverOptions::ALLOW_FW_UPDATE => "--allow-fw-update",
+            DriverOptions::REQUIRE_FW_UPDATE => "--require-fw-update",
+            DriverOptions::LONG_PASSWORD => "--long-password",
+            DriverOptions::BSL_ENTRY_SEQUENCE => "--bsl-entry-sequence",
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// How `mspdebug` reaches the programmer (or target) for a [`TargetDriver`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Connection {
+    Usb,
+    Serial,
+    Parallel,
+    Gpio,
+    Tcp,
+    Simulator,
+}
+
+/// What a [`TargetDriver`] can do, as returned by [`TargetDriver::capabilities`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Capabilities {
+    pub connection: Connection,
+    /// Can talk to the target over 4-wire JTAG.
+    pub jtag: bool,
+    /// Can talk to the target over 2-wire Spy-Bi-Wire.
+    pub sbw: bool,
+    /// Can erase Information Memory segments.
+    pub erase_infomem: bool,
+    /// Can sample target current for power profiling.
+    pub power_profiling: bool,
+    /// Only talks to a bootloader, so there is no run control (no `run`,
+    /// `step`, breakpoints or `gdb`).
+    pub bsl_only: bool,
+    /// [`Cfg`] options this driver takes.
+    pub options: DriverOptions,
+}
+
+impl TargetDriver {
+    /// Capabilities of this driver, used to reject unsupported configurations
+    /// and operations before talking to `mspdebug`.
+    pub fn capabilities(&self) -> Capabilities {
+        use Connection::*;
+
+        #[rustfmt::skip]
+        let (connection, jtag, sbw, erase_infomem, power_profiling, bsl_only) = match self {
+            //                                 jtag   sbw    infomem power  bsl
+            TargetDriver::Rf2500       => (Usb,       false, true,  true,  false, false),
+            TargetDriver::Olimex       => (Usb,       true,  true,  true,  false, false),
+            TargetDriver::Olimexv1     => (Usb,       true,  false, true,  false, false),
+            TargetDriver::OlimexIso    => (Serial,    true,  true,  true,  false, false),
+            TargetDriver::OlimexIsoMk2 => (Usb,       true,  true,  true,  false, false),
+            TargetDriver::Sim          => (Simulator, false, false, true,  false, false),
+            TargetDriver::SimX         => (Simulator, false, false, true,  false, false),
+            TargetDriver::Uif          => (Usb,       true,  true,  true,  false, false),
+            TargetDriver::UifBsl       => (Serial,    false, false, false, false, true),
+            TargetDriver::FlashBsl     => (Serial,    false, false, true,  false, true),
+            TargetDriver::GdbClient    => (Tcp,       false, false, false, false, false),
+            TargetDriver::Tilib        => (Usb,       true,  true,  true,  true,  false),
+            TargetDriver::GoodFet      => (Serial,    true,  false, true,  false, false),
+            TargetDriver::Pif          => (Parallel,  true,  false, true,  false, false),
+            TargetDriver::Gpio         => (Gpio,      true,  false, true,  false, false),
+            TargetDriver::LoadBsl      => (Usb,       false, false, false, false, true),
+            TargetDriver::EzFet        => (Usb,       false, true,  true,  true,  false),
+            TargetDriver::RomBsl       => (Serial,    false, false, true,  false, true),
+            TargetDriver::BusPirate    => (Serial,    true,  false, true,  false, false),
+            TargetDriver::MehFet       => (Usb,       true,  true,  true,  false, false),
+        };
+
+        let fet = DriverOptions::FET;
+        let usb = DriverOptions::USB;
+        let path = DriverOptions::DEVICE_PATH;
+        let options = match self {
+            TargetDriver::Rf2500 | TargetDriver::EzFet => usb | fet,
+            TargetDriver::Uif
+            | TargetDriver::Olimex
+            | TargetDriver::Olimexv1
+            | TargetDriver::OlimexIsoMk2 => DriverOptions::JTAG | path | usb | fet,
+            TargetDriver::OlimexIso => DriverOptions::JTAG | path | fet,
+            TargetDriver::Tilib => {
+                DriverOptions::JTAG | path | DriverOptions::VOLTAGE | DriverOptions::ALLOW_FW_UPDATE
+            }
+            TargetDriver::MehFet => DriverOptions::JTAG | usb,
+            TargetDriver::LoadBsl => usb,
+            TargetDriver::FlashBsl => {
+                path | DriverOptions::LONG_PASSWORD | DriverOptions::BSL_ENTRY_SEQUENCE
+            }
+            TargetDriver::RomBsl => path | DriverOptions::BSL_ENTRY_SEQUENCE,
+            TargetDriver::UifBsl
+            | TargetDriver::GdbClient
+            | TargetDriver::GoodFet
+            | TargetDriver::Pif
+            | TargetDriver::BusPirate => path,
+            TargetDriver::Gpio | TargetDriver::Sim | TargetDriver::SimX => DriverOptions::empty(),
+        };
+
+        Capabilities {
+            connection,
+            jtag,
+            sbw,
+            erase_infomem,
+            power_profiling,
+            bsl_only,
+            // Listing USB devices happens before any driver is opened.
+            options: options | DriverOptions::USB_LIST,
+        }
+    }
+}
+
+/// Values for the `mspdebug` command-line options that take one.
+#[derive(Default)]
+struct OptionValues {
+    device_path: Option<PathBuf>,
+    usb_device: Option<String>,
+    serial: Option<String>,
+    voltage: Option<u32>,
+    fet_force_id: Option<String>,
+    require_fw_update: Option<PathBuf>,
+    bsl_entry_sequence: Option<String>,
+}
+
 pub struct Cfg {
     binary: PathBuf,
     pub(crate) driver: TargetDriver,
     quiet: bool,
     pub(crate) group: bool,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) stderr_lines: usize,
+    pub(crate) on_stderr: Option<StderrCallback>,
+    envs: Vec<(OsString, OsString)>,
+    record: Option<PathBuf>,
+    protect_calibration: bool,
+    calibration_dir: Option<PathBuf>,
+    options: DriverOptions,
+    values: OptionValues,
+}
+
+impl Default for Cfg {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Cfg {
@@ -92,6 +293,15 @@ impl Cfg {
             driver: TargetDriver::Sim,
             quiet: true,
             group: false,
+            timeout: None,
+            stderr_lines: 16,
+            on_stderr: None,
+            envs: Vec::new(),
+            record: None,
+            protect_calibration: false,
+            calibration_dir: None,
+            options: DriverOptions::empty(),
+            values: OptionValues::default(),
         }
     }
 
@@ -111,27 +321,291 @@ impl Cfg {
         Cfg { group, ..self }
     }
 
+    /// Give up on any command that takes longer than `timeout`; see
+    /// [`MspDebug::set_timeout`]. By default, commands wait forever.
+    pub fn timeout(self, timeout: Duration) -> Cfg {
+        Cfg {
+            timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /** Keep the last `lines` lines `mspdebug` prints on stderr.
+
+    Driver-level problems, such as failing to open a USB device, are often
+    only reported on stderr. The captured lines are attached to
+    [`Error::CommsError`] and [`Error::ChildExited`], and are available from
+    [`MspDebug::stderr`]. Defaults to 16 lines; `0` discards stderr.
+    */
+    pub fn capture_stderr(self, lines: usize) -> Cfg {
+        Cfg {
+            stderr_lines: lines,
+            ..self
+        }
+    }
+
+    /// Call `cb` with every line `mspdebug` prints on stderr, as it is printed.
+    /// `cb` runs on a background thread.
+    pub fn on_stderr<F>(self, cb: F) -> Cfg
+    where
+        F: FnMut(&str) + Send + 'static,
+    {
+        Cfg {
+            on_stderr: Some(Box::new(cb)),
+            ..self
+        }
+    }
+
+    /// Set an environment variable for the spawned `mspdebug`, e.g.
+    /// `LD_LIBRARY_PATH` so that the `tilib` driver finds `libmsp430.so`.
+    pub fn env<K, V>(mut self, key: K, val: V) -> Cfg
+    where
+        K: Into<OsString>,
+        V: Into<OsString>,
+    {
+        self.envs.push((key.into(), val.into()));
+        self
+    }
+
+    /** Record the session to a transcript file at `path`.
+
+    Every line sent to `mspdebug` and every line read back is written to the
+    file as it happens, with a timestamp. With the `mock` feature, a recorded
+    session can be loaded with `mock::Transcript::load` and played back with
+    `Cfg::replay` to reproduce a problem without the board.
+    */
+    pub fn record<P>(self, path: P) -> Cfg
+    where
+        P: Into<PathBuf>,
+    {
+        Cfg {
+            record: Some(path.into()),
+            ..self
+        }
+    }
+
+    /** Keep Information Memory segment A intact across erases.
+
+    Segment A holds the factory DCO and ADC calibration on most devices, and
+    isn't always spared by `erase` and `prog`. With this set, segment A is
+    read before every erase or programming command and written back if the
+    command clobbered it. Programming through [`MspDebug::gdb`] restores it
+    after `gdb` loads the ELF file; erases issued from within `gdb` are not
+    covered.
+    */
+    pub fn protect_calibration(self, protect: bool) -> Cfg {
+        Cfg {
+            protect_calibration: protect,
+            ..self
+        }
+    }
+
+    /** Like [`Cfg::protect_calibration`], also backing segment A up to a file
+    in `dir` before each erase.
+
+    There is one backup per device and programmer serial number, e.g.
+    `MSP430G2xx3-0123456789ABCDEF.infoa`; it can be written back with
+    [`MspDebug::restore_segment_a`]. Boards of one family report the same
+    device, so backups are only kept, and restored, when [`Cfg::serial`] is
+    set. Erased segments are never backed up.
+    */
+    pub fn calibration_backups<P>(self, dir: P) -> Cfg
+    where
+        P: Into<PathBuf>,
+    {
+        Cfg {
+            protect_calibration: true,
+            calibration_dir: Some(dir.into()),
+            ..self
+        }
+    }
+
+    fn option(mut self, opt: DriverOptions, enable: bool) -> Cfg {
+        self.options.set(opt, enable);
+        self
+    }
+
+    /// Use JTAG instead of Spy-Bi-Wire (`-j`).
+    pub fn jtag(self, jtag: bool) -> Cfg {
+        self.option(DriverOptions::JTAG, jtag)
+    }
+
+    /// Open the programmer through this tty, parallel port or other device
+    /// path (`-d`). For [`TargetDriver::GdbClient`], this is `host:port`.
+    pub fn device_path<P>(mut self, path: P) -> Cfg
+    where
+        P: Into<PathBuf>,
+    {
+        self.values.device_path = Some(path.into());
+        self.option(DriverOptions::DEVICE_PATH, true)
+    }
+
+    /// Open the USB programmer at this bus and device number (`-U`), as
+    /// printed by `lsusb` or `mspdebug --usb-list`.
+    pub fn usb_device(mut self, bus: u16, device: u16) -> Cfg {
+        self.values.usb_device = Some(format!("{:03}:{:03}", bus, device));
+        self.option(DriverOptions::USB_DEVICE, true)
+    }
+
+    /// Open the USB programmer with this serial number (`-s`). Tells apart
+    /// several identical programmers attached to one host.
+    pub fn serial<S>(mut self, serial: S) -> Cfg
+    where
+        S: Into<String>,
+    {
+        self.values.serial = Some(serial.into());
+        self.option(DriverOptions::SERIAL, true)
+    }
+
+    /// Supply voltage the programmer provides to the target, in millivolts
+    /// (`-v`).
+    pub fn voltage(mut self, millivolts: u32) -> Cfg {
+        self.values.voltage = Some(millivolts);
+        self.option(DriverOptions::VOLTAGE, true)
+    }
+
+    /// Skip device identification and assume this device (`--fet-force-id`).
+    pub fn fet_force_id<S>(mut self, device: S) -> Cfg
+    where
+        S: Into<String>,
+    {
+        self.values.fet_force_id = Some(device.into());
+        self.option(DriverOptions::FET_FORCE_ID, true)
+    }
+
+    /// Don't send the FET close command on exit (`--fet-skip-close`).
+    pub fn fet_skip_close(self, skip: bool) -> Cfg {
+        self.option(DriverOptions::FET_SKIP_CLOSE, skip)
+    }
+
+    /// List USB programmers instead of opening one (`--usb-list`). Only used by
+    /// [`Cfg::list_devices`]; a session started this way exits right away.
+    pub(crate) fn usb_list(self, list: bool) -> Cfg {
+        self.option(DriverOptions::USB_LIST, list)
+    }
+
+    /// Reset the target during initialization (`--force-reset`).
+    pub fn force_reset(self, reset: bool) -> Cfg {
+        self.option(DriverOptions::FORCE_RESET, reset)
+    }
+
+    /// Let the TI library update the programmer's firmware if it is out of
+    /// date (`--allow-fw-update`).
+    pub fn allow_fw_update(self, allow: bool) -> Cfg {
+        self.option(DriverOptions::ALLOW_FW_UPDATE, allow)
+    }
+
+    /// Update the programmer's firmware with this image before opening it
+    /// (`--require-fw-update`).
+    pub fn require_fw_update<P>(mut self, image: P) -> Cfg
+    where
+        P: Into<PathBuf>,
+    {
+        self.values.require_fw_update = Some(image.into());
+        self.option(DriverOptions::REQUIRE_FW_UPDATE, true)
+    }
+
+    /// Send a 32-byte BSL password (`--long-password`).
+    pub fn long_password(self, long: bool) -> Cfg {
+        self.option(DriverOptions::LONG_PASSWORD, long)
+    }
+
+    /// Toggle RTS/DTR in this sequence to enter the BSL
+    /// (`--bsl-entry-sequence`), e.g. `"DR,r,R,r,d,R:DR,r"`.
+    pub fn bsl_entry_sequence<S>(mut self, seq: S) -> Cfg
+    where
+        S: Into<String>,
+    {
+        self.values.bsl_entry_sequence = Some(seq.into());
+        self.option(DriverOptions::BSL_ENTRY_SEQUENCE, true)
+    }
+
+    /// Arguments for the options that were set, rejecting any that the
+    /// driver does not take.
+    fn option_args(&self) -> Result<Vec<OsString>, Error> {
+        let unsupported = self.options - self.driver.capabilities().options;
+        if let Some(opt) = DriverOptions::each().find(|o| unsupported.contains(*o)) {
+            return Err(Error::UnsupportedOption {
+                driver: self.driver,
+                option: opt.flag(),
+            });
+        }
+
+        let mut args = Vec::new();
+        for opt in DriverOptions::each().filter(|o| self.options.contains(*o)) {
+            args.push(opt.flag().into());
+
+            let v = &self.values;
+            let value: Option<OsString> = match opt {
+                DriverOptions::DEVICE_PATH => v.device_path.clone().map(Into::into),
+                DriverOptions::USB_DEVICE => v.usb_device.clone().map(Into::into),
+                DriverOptions::SERIAL => v.serial.clone().map(Into::into),
+                DriverOptions::VOLTAGE => v.voltage.map(|mv| mv.to_string().into()),
+                DriverOptions::FET_FORCE_ID => v.fet_force_id.clone().map(Into::into),
+                DriverOptions::REQUIRE_FW_UPDATE => v.require_fw_update.clone().map(Into::into),
+                DriverOptions::BSL_ENTRY_SEQUENCE => v.bsl_entry_sequence.clone().map(Into::into),
+                _ => None,
+            };
+            args.extend(value);
+        }
+
+        Ok(args)
+    }
+
     // Not part of public API for now. For testing.
     #[allow(unused)]
     fn quiet(self, quiet: bool) -> Cfg {
         Cfg { quiet, ..self }
     }
 
-    pub fn run(self) -> Result<MspDebug, Error> {
+    /// The `mspdebug` command line for this configuration.
+    pub(crate) fn command(&self) -> Result<Command, Error> {
         let mut cmd = Command::new(self.binary.clone());
 
         cmd.args(["--embedded", self.driver.as_ref()]);
+        cmd.envs(self.envs.iter().map(|(k, v)| (k, v)));
 
         if self.quiet {
             cmd.arg("-q");
         }
 
+        cmd.args(self.option_args()?);
+
+        Ok(cmd)
+    }
+
+    pub(crate) fn safeguard(&self) -> Option<Safeguard> {
+        self.protect_calibration
+            .then(|| Safeguard::new(self.calibration_dir.clone(), self.values.serial.clone()))
+    }
+
+    /// Supply voltage `mspdebug` runs the target at, in millivolts.
+    pub(crate) fn millivolts(&self) -> u32 {
+        self.values.voltage.unwrap_or(DEFAULT_MILLIVOLTS)
+    }
+
+    pub(crate) fn recorder(&self, header: &str) -> Result<Option<Recorder>, Error> {
+        self.record
+            .as_ref()
+            .map(|p| Recorder::create(p, header))
+            .transpose()
+            .map_err(Error::RecordError)
+    }
+
+    pub fn run(self) -> Result<MspDebug, Error> {
+        let mut cmd = self.command()?;
+        let recorder = self.recorder(&format!("{:?}", cmd))?;
+
         let child_cfg = cmd
-            .stderr(Stdio::null())
+            .stderr(if self.stderr_lines > 0 || self.on_stderr.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
             .stdin(Stdio::piped())
             .stdout(Stdio::piped());
 
-        let mut child = if self.group {
+        let child = if self.group {
             let mut child_group_cfg = child_cfg.group();
 
             // Process groups and job objects are separate on Windows, but might
@@ -139,17 +613,64 @@ impl Cfg {
             #[cfg(windows)]
             child_group_cfg.creation_flags(CREATE_NEW_PROCESS_GROUP);
 
-            child_group_cfg
-                .spawn()
-                .map_err(Error::SpawnError)?
-                .into_inner()
+            // Keep the group handle around so a timeout can kill the whole
+            // group.
+            Process::Group(child_group_cfg.spawn().map_err(Error::SpawnError)?)
         } else {
-            child_cfg.spawn().map_err(Error::SpawnError)?
+            Process::Single(child_cfg.spawn().map_err(Error::SpawnError)?)
         };
 
-        let stdin = child.stdin.take().ok_or(Error::StreamError("stdin"))?;
-        let stdout = child.stdout.take().ok_or(Error::StreamError("stdout"))?;
+        MspDebug::new(Box::new(ProcessTransport::new(child)?), recorder, self)
+    }
+
+    /** Start a session with an `mspdebug` reached over `transport`, instead of
+    spawning one.
+
+    `mspdebug` must already be running in embedded mode with the configured
+    driver; the driver and its options are not sent anywhere, but the driver
+    still decides what the session supports.
+    */
+    pub fn open<T>(self, transport: T) -> Result<MspDebug, Error>
+    where
+        T: Transport + 'static,
+    {
+        let recorder = self.recorder(any::type_name::<T>())?;
+        MspDebug::new(Box::new(transport), recorder, self)
+    }
+
+    /** Start a session with an `mspdebug` listening on a TCP socket, such as
+    one started on a lab machine with:
+
+    ```text
+    socat TCP-LISTEN:2001,reuseaddr EXEC:"mspdebug --embedded rf2500 -q"
+    ```
+
+    See [`Cfg::open`].
+    */
+    pub fn connect<A>(self, addr: A) -> Result<MspDebug, Error>
+    where
+        A: ToSocketAddrs,
+    {
+        let stream = TcpStream::connect(addr).map_err(Error::ConnectError)?;
+        // Commands are single short lines; don't sit on them.
+        stream.set_nodelay(true).map_err(Error::ConnectError)?;
+
+        self.open(stream)
+    }
+
+    /** Play back `transcript` in place of running `mspdebug`.
+
+    The session behaves as if `mspdebug` had printed the transcript's output
+    lines, as long as the commands sent match its input lines. Otherwise, the
+    command that went astray fails with [`Error::CommsError`]. The configured
+    driver still decides what the session supports, so it should match the one
+    the transcript was recorded with.
+    */
+    #[cfg(feature = "mock")]
+    pub fn replay(self, transcript: Transcript) -> Result<MspDebug, Error> {
+        let recorder = self.recorder("replay")?;
+        let replay = Replay::new(transcript).map_err(Error::SpawnError)?;
 
-        Ok(MspDebug::new(child, stdin, stdout, self))
+        MspDebug::new(Box::new(replay), recorder, self)
     }
 }
@@ -0,0 +1,172 @@
+use std::ops::Range;
+
+use crate::devicemap::DEVICE_MAP;
+use crate::error::FitReason;
+
+/// The CPU of a device.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Core {
+    /// The original 16-bit CPU.
+    Cpu,
+    /// MSP430X, with 20-bit addresses (larger 2xx and 4xx parts).
+    CpuX,
+    /// MSP430Xv2 (5xx, 6xx and FRAM parts).
+    CpuXv2,
+}
+
+/// What main memory is made of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Technology {
+    Flash,
+    Fram,
+}
+
+/// Information Memory of a device.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct InfoMem {
+    pub origin: u32,
+    pub length: u32,
+    /// Size of one erasable segment.
+    pub segment_size: u32,
+}
+
+impl InfoMem {
+    pub fn range(&self) -> Range<u32> {
+        self.origin..self.origin + self.length
+    }
+}
+
+/** Memory map of a device, see [`DeviceInfo::lookup`].
+
+The table is generated by `scripts/mkphf.py` from the inputs in `scripts/`;
+the header of `devicemap.rs` says where they came from.
+
+Ranges exclude their end address. Family names `mspdebug` reports instead of a
+part number (e.g. `MSP430G2xx3`) get the memory map of the largest member of
+the family.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct DeviceInfo {
+    pub core: Core,
+    pub technology: Technology,
+    /// Main flash or FRAM, up to the vector table. Parts with more than
+    /// 64 KiB have a second range from `0x10000`.
+    pub main: &'static [Range<u32>],
+    /// RAM, including USB RAM.
+    pub ram: Range<u32>,
+    /// Special function and peripheral registers.
+    pub peripherals: Range<u32>,
+    /// Bootstrap loader, if the part has one.
+    pub bsl: Option<Range<u32>>,
+    pub infomem: Option<InfoMem>,
+    /// Start of the interrupt vector table, which ends at `0x10000`.
+    pub vectors: u32,
+}
+
+/// The smallest value line families, which the larger ones build on.
+#[allow(clippy::single_range_in_vec_init)]
+const SMALL_VALUE_LINE: DeviceInfo = DeviceInfo {
+    core: Core::Cpu,
+    technology: Technology::Flash,
+    main: &[0xf800..0xffe0],
+    ram: 0x200..0x280,
+    peripherals: 0x0..0x200,
+    bsl: None,
+    infomem: Some(InfoMem {
+        origin: 0x1000,
+        length: 0x100,
+        segment_size: 64,
+    }),
+    vectors: 0xffe0,
+};
+
+/// Families `mspdebug` reports by name, mapped to their largest member.
+#[allow(clippy::single_range_in_vec_init)]
+static FAMILIES: [(&str, DeviceInfo); 4] = [
+    ("F20x1_G2x0x_G2x1x", SMALL_VALUE_LINE),
+    ("F20x2_G2x2x_G2x3x", SMALL_VALUE_LINE),
+    (
+        "MSP430G2xx2",
+        DeviceInfo {
+            main: &[0xe000..0xffe0],
+            ram: 0x200..0x300,
+            ..SMALL_VALUE_LINE
+        },
+    ),
+    (
+        "MSP430G2xx3",
+        DeviceInfo {
+            main: &[0xc000..0xffe0],
+            ram: 0x200..0x400,
+            bsl: Some(0xc00..0x1000),
+            ..SMALL_VALUE_LINE
+        },
+    ),
+];
+
+impl DeviceInfo {
+    /// Look up a device by the name `mspdebug` reports for it, as returned by
+    /// [`MspDebug::device`](crate::MspDebug::device).
+    pub fn lookup(device: &str) -> Option<&'static DeviceInfo> {
+        DEVICE_MAP.get(device).and_then(Option::as_ref).or_else(|| {
+            FAMILIES
+                .iter()
+                .find(|(name, _)| *name == device)
+                .map(|(_, info)| info)
+        })
+    }
+
+    /// Size of main memory, including the vector table, in bytes.
+    // Only used by the tests until DeviceInfo is public again.
+    #[allow(dead_code)]
+    pub fn main_size(&self) -> u32 {
+        let main: u32 = self.main.iter().map(|r| r.end - r.start).sum();
+        main + (0x10000 - self.vectors)
+    }
+
+    /// The vector table.
+    pub fn vector_table(&self) -> Range<u32> {
+        self.vectors..0x10000
+    }
+
+    /** First address in `range` that cannot be programmed, and why.
+
+    Programs may be loaded into main memory, the vector table, Information
+    Memory and RAM, but not over the bootstrap loader.
+    */
+    pub(crate) fn misfit(&self, range: Range<u32>) -> Option<(u32, FitReason)> {
+        if let Some(bsl) = &self.bsl {
+            if bsl.start < range.end && range.start < bsl.end {
+                return Some((range.start.max(bsl.start), FitReason::Bsl));
+            }
+        }
+
+        let mut memory = self.main.to_vec();
+        memory.push(self.vector_table());
+        memory.push(self.ram.clone());
+        memory.extend(self.infomem.map(|i| i.range()));
+
+        let mut addr = range.start;
+        while addr < range.end {
+            match memory.iter().find(|r| r.contains(&addr)) {
+                Some(r) => addr = r.end,
+                None => return Some((addr, self.misfit_reason(addr))),
+            }
+        }
+
+        None
+    }
+
+    /// Addresses between RAM and main memory, or past the end of main memory,
+    /// are where larger parts have more of it.
+    fn misfit_reason(&self, addr: u32) -> FitReason {
+        let start = self.main.first().map_or(self.vectors, |r| r.start);
+        let end = self.main.last().map_or(0x10000, |r| r.end.max(0x10000));
+
+        if (self.ram.end..start).contains(&addr) || addr >= end {
+            FitReason::TooLarge
+        } else {
+            FitReason::OutsideMemory
+        }
+    }
+}
@@ -0,0 +1,313 @@
+// Most parts have a single range of main memory.
+#![allow(clippy::single_range_in_vec_init)]
+
+// Not extracted from TI's msp430-gcc-support-files: written by
+// scripts/reconstruct.py from datasheet flash and RAM sizes per family. Run
+// scripts/devicelist.sh against msp430-gcc-support-files to replace it.
+
+use phf::{phf_map, Map};
+
+use crate::device::{Core, DeviceInfo, InfoMem, Technology};
+
+/* Autogenerated by mkphf.py */
+pub(crate) static DEVICE_MAP: Map<&'static str, Option<DeviceInfo>> = phf_map! {
+/* Extracted from mspmemory.txt and mspcores.txt. */
+"CC430F5123" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xE000..0xFF80], ram: 0x1C00..0x2400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"CC430F5125" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xC000..0xFF80], ram: 0x1C00..0x2400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"CC430F5133" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xE000..0xFF80], ram: 0x1C00..0x2400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"CC430F5135" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xC000..0xFF80], ram: 0x1C00..0x2400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"CC430F5137" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x8000..0xFF80], ram: 0x1C00..0x2C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"CC430F5143" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xE000..0xFF80], ram: 0x1C00..0x2400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"CC430F5145" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xC000..0xFF80], ram: 0x1C00..0x2400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"CC430F5147" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x8000..0xFF80], ram: 0x1C00..0x2C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"CC430F6125" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xC000..0xFF80], ram: 0x1C00..0x2400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"CC430F6126" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x8000..0xFF80], ram: 0x1C00..0x2400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"CC430F6127" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x8000..0xFF80], ram: 0x1C00..0x2C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"CC430F6135" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xC000..0xFF80], ram: 0x1C00..0x2400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"CC430F6137" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x8000..0xFF80], ram: 0x1C00..0x2C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"CC430F6143" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xE000..0xFF80], ram: 0x1C00..0x2400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"CC430F6145" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xC000..0xFF80], ram: 0x1C00..0x2400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"CC430F6147" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x8000..0xFF80], ram: 0x1C00..0x2C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430AFE221" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xF000..0xFFE0], ram: 0x200..0x300, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFE0 }),
+"MSP430AFE222" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xE000..0xFFE0], ram: 0x200..0x400, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFE0 }),
+"MSP430AFE223" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xD000..0xFFE0], ram: 0x200..0x400, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFE0 }),
+"MSP430AFE231" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xF000..0xFFE0], ram: 0x200..0x300, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFE0 }),
+"MSP430AFE232" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xE000..0xFFE0], ram: 0x200..0x400, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFE0 }),
+"MSP430AFE233" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xD000..0xFFE0], ram: 0x200..0x400, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFE0 }),
+"MSP430AFE251" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xF000..0xFFE0], ram: 0x200..0x300, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFE0 }),
+"MSP430AFE252" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xE000..0xFFE0], ram: 0x200..0x400, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFE0 }),
+"MSP430AFE253" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xD000..0xFFE0], ram: 0x200..0x400, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFE0 }),
+"MSP430F133" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xE000..0xFFE0], ram: 0x200..0x300, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F135" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xC000..0xFFE0], ram: 0x200..0x400, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F147" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x8000..0xFFE0], ram: 0x200..0x600, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F148" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x4000..0xFFE0], ram: 0x200..0xA00, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F149" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x1100..0xFFE0], ram: 0x200..0xA00, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F155" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xC000..0xFFE0], ram: 0x200..0x400, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F156" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xA000..0xFFE0], ram: 0x200..0x600, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F157" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x8000..0xFFE0], ram: 0x200..0x600, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F1610" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x8000..0xFFE0], ram: 0x1100..0x2500, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F1611" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x4000..0xFFE0], ram: 0x1100..0x3900, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F1612" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x2500..0xFFE0], ram: 0x1100..0x2500, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F167" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x8000..0xFFE0], ram: 0x200..0x600, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F168" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x4000..0xFFE0], ram: 0x200..0xA00, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F169" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x1100..0xFFE0], ram: 0x200..0xA00, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F2112" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xF800..0xFFE0], ram: 0x200..0x300, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFE0 }),
+"MSP430F2122" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xF000..0xFFE0], ram: 0x200..0x400, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFE0 }),
+"MSP430F2132" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xE000..0xFFE0], ram: 0x200..0x400, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFE0 }),
+"MSP430F2232" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xE000..0xFFE0], ram: 0x200..0x400, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFE0 }),
+"MSP430F2234" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xE000..0xFFE0], ram: 0x200..0x400, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFE0 }),
+"MSP430F2252" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xC000..0xFFE0], ram: 0x200..0x400, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFE0 }),
+"MSP430F2254" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xC000..0xFFE0], ram: 0x200..0x400, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFE0 }),
+"MSP430F2272" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x8000..0xFFE0], ram: 0x200..0x600, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFE0 }),
+"MSP430F2274" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x8000..0xFFE0], ram: 0x200..0x600, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFE0 }),
+"MSP430F233" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xE000..0xFFE0], ram: 0x200..0x600, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFE0 }),
+"MSP430F2330" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xE000..0xFFC0], ram: 0x200..0x600, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+"MSP430F235" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xC000..0xFFE0], ram: 0x200..0xA00, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFE0 }),
+"MSP430F2350" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xC000..0xFFC0], ram: 0x200..0xA00, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+"MSP430F2370" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x8000..0xFFC0], ram: 0x200..0xA00, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+"MSP430F2410" => Some(DeviceInfo { core: Core::CpuX, technology: Technology::Flash, main: &[0x2100..0xFFC0], ram: 0x1100..0x2100, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+"MSP430F2418" => Some(DeviceInfo { core: Core::CpuX, technology: Technology::Flash, main: &[0x3100..0xFFC0, 0x10000..0x20000], ram: 0x1100..0x3100, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+"MSP430F2419" => Some(DeviceInfo { core: Core::CpuX, technology: Technology::Flash, main: &[0x2100..0xFFC0, 0x10000..0x20000], ram: 0x1100..0x2100, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+"MSP430F247" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x8000..0xFFC0], ram: 0x1100..0x2100, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+"MSP430F2471" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x8000..0xFFC0], ram: 0x1100..0x2100, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+"MSP430F248" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x4000..0xFFC0], ram: 0x1100..0x2100, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+"MSP430F2481" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x4000..0xFFC0], ram: 0x1100..0x2100, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+"MSP430F249" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x1100..0xFFC0], ram: 0x200..0xA00, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+"MSP430F2491" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x1100..0xFFC0], ram: 0x200..0xA00, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+"MSP430F2618" => Some(DeviceInfo { core: Core::CpuX, technology: Technology::Flash, main: &[0x3100..0xFFC0, 0x10000..0x20000], ram: 0x1100..0x3100, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+"MSP430F2619" => Some(DeviceInfo { core: Core::CpuX, technology: Technology::Flash, main: &[0x2100..0xFFC0, 0x10000..0x20000], ram: 0x1100..0x2100, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+"MSP430F423" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xE000..0xFFE0], ram: 0x200..0x300, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F423A" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xE000..0xFFE0], ram: 0x200..0x300, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F425" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xC000..0xFFE0], ram: 0x200..0x400, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F4250" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xC000..0xFFE0], ram: 0x200..0x300, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F425A" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xC000..0xFFE0], ram: 0x200..0x400, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F427" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x8000..0xFFE0], ram: 0x200..0x600, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F427A" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x8000..0xFFE0], ram: 0x200..0x600, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430F477" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x8000..0xFFC0], ram: 0x200..0xA00, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+"MSP430F478" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x4000..0xFFC0], ram: 0x200..0xA00, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+"MSP430F479" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x1100..0xFFC0], ram: 0x200..0xA00, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+"MSP430F5131" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xE000..0xFF80], ram: 0x1C00..0x2000, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5132" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xE000..0xFF80], ram: 0x1C00..0x2000, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5151" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xC000..0xFF80], ram: 0x1C00..0x2400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5152" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xC000..0xFF80], ram: 0x1C00..0x2400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5171" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x8000..0xFF80], ram: 0x1C00..0x2400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5172" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x8000..0xFF80], ram: 0x1C00..0x2400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5212" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x3C00..0xFF80, 0x10000..0x13C00], ram: 0x1C00..0x3C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5213" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x3C00..0xFF80, 0x10000..0x1BC00], ram: 0x1C00..0x3C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5214" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x3C00..0xFF80, 0x10000..0x23C00], ram: 0x1C00..0x3C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5217" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x3C00..0xFF80, 0x10000..0x13C00], ram: 0x1C00..0x3C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5218" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x3C00..0xFF80, 0x10000..0x1BC00], ram: 0x1C00..0x3C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5219" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x3C00..0xFF80, 0x10000..0x23C00], ram: 0x1C00..0x3C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5222" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x3C00..0xFF80, 0x10000..0x13C00], ram: 0x1C00..0x3C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5223" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x3C00..0xFF80, 0x10000..0x1BC00], ram: 0x1C00..0x3C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5224" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x3C00..0xFF80, 0x10000..0x23C00], ram: 0x1C00..0x3C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5227" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x3C00..0xFF80, 0x10000..0x13C00], ram: 0x1C00..0x3C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5228" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x3C00..0xFF80, 0x10000..0x1BC00], ram: 0x1C00..0x3C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5229" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x3C00..0xFF80, 0x10000..0x23C00], ram: 0x1C00..0x3C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5304" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xE000..0xFF80], ram: 0x1C00..0x3400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5308" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xC000..0xFF80], ram: 0x1C00..0x3400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5309" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xA000..0xFF80], ram: 0x1C00..0x3400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5310" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x8000..0xFF80], ram: 0x1C00..0x3400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5418" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x5C00..0xFF80, 0x10000..0x25C00], ram: 0x1C00..0x5C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5418A" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x5C00..0xFF80, 0x10000..0x25C00], ram: 0x1C00..0x5C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5419" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x5C00..0xFF80, 0x10000..0x25C00], ram: 0x1C00..0x5C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5419A" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x5C00..0xFF80, 0x10000..0x25C00], ram: 0x1C00..0x5C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5435" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x5C00..0xFF80, 0x10000..0x35C00], ram: 0x1C00..0x5C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5435A" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x5C00..0xFF80, 0x10000..0x35C00], ram: 0x1C00..0x5C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5436" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x5C00..0xFF80, 0x10000..0x35C00], ram: 0x1C00..0x5C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5436A" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x5C00..0xFF80, 0x10000..0x35C00], ram: 0x1C00..0x5C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5437" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x5C00..0xFF80, 0x10000..0x45C00], ram: 0x1C00..0x5C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5437A" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x5C00..0xFF80, 0x10000..0x45C00], ram: 0x1C00..0x5C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5438" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x5C00..0xFF80, 0x10000..0x45C00], ram: 0x1C00..0x5C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5438A" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x5C00..0xFF80, 0x10000..0x45C00], ram: 0x1C00..0x5C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5500" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xE000..0xFF80], ram: 0x1C00..0x3400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5501" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xC000..0xFF80], ram: 0x1C00..0x3400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5502" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xA000..0xFF80], ram: 0x1C00..0x3400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5503" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x8000..0xFF80], ram: 0x1C00..0x3400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5504" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xE000..0xFF80], ram: 0x1C00..0x3400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5505" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xC000..0xFF80], ram: 0x1C00..0x3400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5506" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xA000..0xFF80], ram: 0x1C00..0x3400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5507" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x8000..0xFF80], ram: 0x1C00..0x3400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5508" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xC000..0xFF80], ram: 0x1C00..0x3400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5509" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0xA000..0xFF80], ram: 0x1C00..0x3400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5510" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x8000..0xFF80], ram: 0x1C00..0x3400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5513" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x8000..0xFF80], ram: 0x1C00..0x3400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5514" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x3400..0xFF80, 0x10000..0x13400], ram: 0x1C00..0x3400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5515" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x3400..0xFF80, 0x10000..0x13400], ram: 0x1C00..0x3400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5517" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x3C00..0xFF80, 0x10000..0x1BC00], ram: 0x1C00..0x3C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5519" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x4400..0xFF80, 0x10000..0x24400], ram: 0x1C00..0x4400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5524" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x3400..0xFF80, 0x10000..0x13400], ram: 0x1C00..0x3400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5525" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x3400..0xFF80, 0x10000..0x13400], ram: 0x1C00..0x3400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5526" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x3C00..0xFF80, 0x10000..0x1BC00], ram: 0x1C00..0x3C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5527" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x3C00..0xFF80, 0x10000..0x1BC00], ram: 0x1C00..0x3C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5528" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x4400..0xFF80, 0x10000..0x24400], ram: 0x1C00..0x4400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430F5529" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Flash, main: &[0x4400..0xFF80, 0x10000..0x24400], ram: 0x1C00..0x4400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430FE423" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xE000..0xFFE0], ram: 0x200..0x300, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430FE4232" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xE000..0xFFE0], ram: 0x200..0x300, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430FE423A" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xE000..0xFFE0], ram: 0x200..0x300, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430FE425" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xC000..0xFFE0], ram: 0x200..0x400, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430FE425A" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xC000..0xFFE0], ram: 0x200..0x400, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430FE427" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x8000..0xFFE0], ram: 0x200..0x600, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430FE4272" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x8000..0xFFE0], ram: 0x200..0x600, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430FE427A" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x8000..0xFFE0], ram: 0x200..0x600, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430FG4250" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0xC000..0xFFE0], ram: 0x200..0x300, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430FG4618" => Some(DeviceInfo { core: Core::CpuX, technology: Technology::Flash, main: &[0x3100..0xFFC0, 0x10000..0x20000], ram: 0x1100..0x3100, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFC0 }),
+"MSP430FG4619" => Some(DeviceInfo { core: Core::CpuX, technology: Technology::Flash, main: &[0x2100..0xFFC0, 0x10000..0x20000], ram: 0x1100..0x2100, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFC0 }),
+"MSP430FG477" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x8000..0xFFC0], ram: 0x200..0xA00, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+"MSP430FG478" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x4000..0xFFC0], ram: 0x200..0xA00, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+"MSP430FG479" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x1100..0xFFC0], ram: 0x200..0xA00, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+"MSP430FR5729" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Fram, main: &[0xC200..0xFF80], ram: 0x1C00..0x2000, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x100, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430FR5739" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Fram, main: &[0xC200..0xFF80], ram: 0x1C00..0x2000, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x100, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430FR5949" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Fram, main: &[0x4400..0xFF80, 0x10000..0x14000], ram: 0x1C00..0x2400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430FR5959" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Fram, main: &[0x4400..0xFF80, 0x10000..0x14000], ram: 0x1C00..0x2400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430FR5969" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Fram, main: &[0x4400..0xFF80, 0x10000..0x14000], ram: 0x1C00..0x2400, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430FR5994" => Some(DeviceInfo { core: Core::CpuXv2, technology: Technology::Fram, main: &[0x4000..0xFF80, 0x10000..0x44000], ram: 0x1C00..0x2C00, peripherals: 0x0..0x1000, bsl: Some(0x1000..0x1800), infomem: Some(InfoMem { origin: 0x1800, length: 0x200, segment_size: 128 }), vectors: 0xFF80 }),
+"MSP430FW429" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x1100..0xFFE0], ram: 0x200..0xA00, peripherals: 0x0..0x200, bsl: Some(0xC00..0x1000), infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 128 }), vectors: 0xFFE0 }),
+"MSP430G2955" => Some(DeviceInfo { core: Core::Cpu, technology: Technology::Flash, main: &[0x2100..0xFFC0], ram: 0x1100..0x2100, peripherals: 0x0..0x200, bsl: None, infomem: Some(InfoMem { origin: 0x1000, length: 0x100, segment_size: 64 }), vectors: 0xFFC0 }),
+/* Not in mspmemory.txt, or missing a required region. */
+"Device_unknown" => None,
+"F20x1_G2x0x_G2x1x" => None,
+"F20x2_G2x2x_G2x3x" => None,
+"MSP430AFE220" => None,
+"MSP430AFE230" => None,
+"MSP430AFE250" => None,
+"MSP430F11x1" => None,
+"MSP430F11x1A" => None,
+"MSP430F11x2" => None,
+"MSP430F12x" => None,
+"MSP430F12x2/F11x2" => None,
+"MSP430F20x3" => None,
+"MSP430F21x1" => None,
+"MSP430F4132" => None,
+"MSP430F4152" => None,
+"MSP430F41x" => None,
+"MSP430F4230" => None,
+"MSP430F42x0" => None,
+"MSP430F43x" => None,
+"MSP430F44x" => None,
+"MSP430F47166" => None,
+"MSP430F47167" => None,
+"MSP430F47176" => None,
+"MSP430F47177" => None,
+"MSP430F47186" => None,
+"MSP430F47187" => None,
+"MSP430F47196" => None,
+"MSP430F47197" => None,
+"MSP430F4783" => None,
+"MSP430F4784" => None,
+"MSP430F4793" => None,
+"MSP430F4794" => None,
+"MSP430F5255" => None,
+"MSP430F5324" => None,
+"MSP430F5325" => None,
+"MSP430F5326" => None,
+"MSP430F5327" => None,
+"MSP430F5328" => None,
+"MSP430F5329" => None,
+"MSP430F5333" => None,
+"MSP430F5335" => None,
+"MSP430F5336" => None,
+"MSP430F5338" => None,
+"MSP430F5340" => None,
+"MSP430F5341" => None,
+"MSP430F5342" => None,
+"MSP430F5357" => None,
+"MSP430F5358" => None,
+"MSP430F5359" => None,
+"MSP430F5521" => None,
+"MSP430F5522" => None,
+"MSP430F5630" => None,
+"MSP430F5631" => None,
+"MSP430F5632" => None,
+"MSP430F5633" => None,
+"MSP430F5634" => None,
+"MSP430F5635" => None,
+"MSP430F5636" => None,
+"MSP430F5637" => None,
+"MSP430F5638" => None,
+"MSP430F5658" => None,
+"MSP430F5659" => None,
+"MSP430F6433" => None,
+"MSP430F6435" => None,
+"MSP430F6436" => None,
+"MSP430F6438" => None,
+"MSP430F6457" => None,
+"MSP430F6458" => None,
+"MSP430F6459" => None,
+"MSP430F6630" => None,
+"MSP430F6631" => None,
+"MSP430F6632" => None,
+"MSP430F6633" => None,
+"MSP430F6634" => None,
+"MSP430F6635" => None,
+"MSP430F6636" => None,
+"MSP430F6637" => None,
+"MSP430F6638" => None,
+"MSP430F6658" => None,
+"MSP430F6659" => None,
+"MSP430F6700" => None,
+"MSP430F6701" => None,
+"MSP430F6702" => None,
+"MSP430F6703" => None,
+"MSP430F6720" => None,
+"MSP430F6721" => None,
+"MSP430F6722" => None,
+"MSP430F6723" => None,
+"MSP430F6724" => None,
+"MSP430F6725" => None,
+"MSP430F6726" => None,
+"MSP430F6730" => None,
+"MSP430F6731" => None,
+"MSP430F6732" => None,
+"MSP430F6733" => None,
+"MSP430F6734" => None,
+"MSP430F6735" => None,
+"MSP430F6736" => None,
+"MSP430FE423_N" => None,
+"MSP430FE423_P" => None,
+"MSP430FE425_N" => None,
+"MSP430FE425_P" => None,
+"MSP430FE427_N" => None,
+"MSP430FE427_P" => None,
+"MSP430FE42x2" => None,
+"MSP430FG42x0" => None,
+"MSP430FG43x_F43x" => None,
+"MSP430FR5720" => None,
+"MSP430FR5721" => None,
+"MSP430FR5722" => None,
+"MSP430FR5723" => None,
+"MSP430FR5724" => None,
+"MSP430FR5725" => None,
+"MSP430FR5726" => None,
+"MSP430FR5727" => None,
+"MSP430FR5728" => None,
+"MSP430FR5730" => None,
+"MSP430FR5731" => None,
+"MSP430FR5732" => None,
+"MSP430FR5733" => None,
+"MSP430FR5734" => None,
+"MSP430FR5735" => None,
+"MSP430FR5736" => None,
+"MSP430FR5737" => None,
+"MSP430FR5738" => None,
+"MSP430FR5947" => None,
+"MSP430FR5948" => None,
+"MSP430FR5957" => None,
+"MSP430FR5958" => None,
+"MSP430FR5964" => None,
+"MSP430FR5967" => None,
+"MSP430FR5968" => None,
+"MSP430FW42x/F41x" => None,
+"MSP430G2xx2" => None,
+"MSP430G2xx3" => None,
+"MSP430L092" => None,
+"Preliminary_MSP430F12x2" => None,
+"Preliminary_MSP430F16x" => None,
+"Preliminary_MSP430F413" => None,
+"Prototype_MSP430F11x1" => None,
+"TMS430EMU" => None,
+"TMS430FCAS001" => None,
+"TMS430FCAS003" => None,
+"TMS430ROMSHUTTLE" => None,
+"XMS430F5438" => None,
+};
@@ -0,0 +1,22 @@
+/// How serious a [`Diagnostic`] is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    /// `mspdebug` printed a warning, e.g. a firmware version mismatch, or an
+    /// ELF file could not be checked against, or doesn't fit, the device.
+    Warning,
+    /** `mspdebug` printed an error that is known to be harmless and was
+    therefore not turned into an [`Error`](crate::Error), e.g. a FET NAK that
+    `mspdebug` retries on its own.
+    */
+    IgnoredError,
+}
+
+/// A warning or ignored error that came up while running a command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// The message, without the embedded-mode sigil or line ending.
+    pub message: String,
+}
+
+pub(crate) type DiagnosticCallback = Box<dyn FnMut(&Diagnostic) + Send>;
@@ -1,52 +1,43 @@
-use std::fs::File;
 use std::io::{self, BufRead};
 use std::path::Path;
-use std::process::{Child, ChildStdin, ChildStdout, Command, ExitStatus};
+use std::process::{Command, ExitStatus};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
-use elf::endian::LittleEndian;
 use io::Write as _;
 
 use bitflags::bitflags;
-use elf::ElfStream;
 
-use crate::error::BadInputReason;
-use crate::TargetDriver;
+use crate::breakpoint::{Breakpoint, BreakpointKind, Stop};
+use crate::calibration::is_erased;
+use crate::diagnostic::Diagnostic;
+use crate::event::Event;
+use crate::ops::{self, Op, Reply, Step};
+use crate::power::PowerProfile;
+use crate::protocol::{mw_command, Protocol, Tail, WaitMode, MW_CHUNK_SIZE};
+use crate::regs::{Register, Registers};
+use crate::tlv::Calibration;
+use crate::transcript::Recorder;
+use crate::transport::Transport;
 
 use super::{infomem::INFOMEM_MAP, Cfg, Error};
 
-enum OutputType<'a> {
-    Normal(&'a str),
-    Debug(&'a str),
-    Error(ErrorSeverity<'a>),
-    Shell(ShellType),
-}
-
-enum ErrorSeverity<'a> {
-    Warning(&'a str),
-    Error(&'a str),
-}
-
-pub(crate) enum ShellType {
-    Ready,
-    Busy,
-    PowerSampleUs,
-    PowerSamples,
-}
+pub(crate) type StderrCallback = Box<dyn FnMut(&str) + Send>;
 
-#[derive(PartialEq)]
-enum WaitMode {
-    Ready,
-    Busy,
-}
+/** A running `mspdebug` session in embedded mode.
 
+Created by [`Cfg::run`], or [`Cfg::open`] for an `mspdebug` reached some
+other way. Each method sends one or more `mspdebug` commands and
+waits for `mspdebug` to become ready again before returning, so methods can be
+called back-to-back without additional synchronization.
+*/
 pub struct MspDebug {
-    stdin: ChildStdin,
-    stdout: io::BufReader<ChildStdout>,
-    cfg: Cfg,
-    last_shelltype: Option<ShellType>,
-    child: Child,
+    transport: Box<dyn Transport>,
+    stdout: Receiver<io::Result<String>>,
+    stderr_reader: Option<JoinHandle<()>>,
     need_drop: bool,
-    device: Option<String>,
+    proto: Protocol,
 }
 
 bitflags! {
@@ -65,7 +56,7 @@ bitflags! {
 
 pub struct GdbCfg {
     flags: GdbConfigFlags,
-    port: u16,
+    pub(crate) port: u16,
     extra_args: Vec<String>,
 }
 
@@ -94,143 +85,613 @@ impl GdbCfg {
         self.extra_args = cmds;
         self
     }
+
+    /** `msp430-elf-gdb` arguments for this configuration; `im` is the
+    Information Memory range to erase, if any, and `seg_a` the address and
+    contents of segment A to restore after erasing.
+    */
+    pub(crate) fn args(
+        &self,
+        filename: &Path,
+        im: Vec<(u16, u16, u16)>,
+        seg_a: Option<(u32, Vec<u8>)>,
+    ) -> Vec<String> {
+        let mut args = Vec::new();
+
+        if self.flags.contains(GdbConfigFlags::QUIET) {
+            args.push("-q".to_owned());
+        }
+
+        let mut ex = |cmd: String| args.extend(["-ex".to_owned(), cmd]);
+        ex(format!("target remote localhost:{}", self.port));
+
+        if self.flags.contains(GdbConfigFlags::ERASE) {
+            ex("monitor erase".to_owned());
+        }
+
+        if self.flags.contains(GdbConfigFlags::ERASE_INFOMEM) {
+            for (origin, length, sector_size) in im {
+                ex(format!(
+                    "monitor erase segrange {} {} {}",
+                    origin, length, sector_size
+                ));
+            }
+        }
+
+        if self.flags.contains(GdbConfigFlags::LOAD) {
+            ex("load".to_owned());
+        }
+
+        // Writing segment A back unchanged is harmless, and gdb can't tell
+        // whether the erase clobbered it.
+        if let (true, Some((addr, data))) = (self.flags.contains(GdbConfigFlags::ERASE), seg_a) {
+            if !is_erased(&data) {
+                for (i, chunk) in data.chunks(MW_CHUNK_SIZE).enumerate() {
+                    let chunk_addr = addr + (i * MW_CHUNK_SIZE) as u32;
+                    ex(format!("monitor {}", mw_command(chunk_addr, chunk)));
+                }
+            }
+        }
+
+        ex("monitor reset".to_owned());
+
+        for arg in self.extra_args.iter() {
+            ex(arg.clone());
+        }
+
+        args.push(filename.to_string_lossy().into_owned());
+        args
+    }
 }
 
-impl MspDebug {
-    pub(crate) fn new(child: Child, stdin: ChildStdin, stdout: ChildStdout, cfg: Cfg) -> Self {
+bitflags! {
+    pub(crate) struct ProgramFlags: u32 {
+        const VERIFY = 1;
+        const ALLOW_MISFIT = 1 << 1;
+
+        const DEFAULT = 0;
+    }
+}
+
+/// Options for [`MspDebug::program_with`].
+pub struct ProgramOptions {
+    pub(crate) flags: ProgramFlags,
+}
+
+impl Default for ProgramOptions {
+    fn default() -> Self {
         Self {
-            stdin,
-            stdout: io::BufReader::new(stdout),
-            cfg,
-            last_shelltype: None,
-            child,
-            need_drop: false,
-            device: None,
+            flags: ProgramFlags::DEFAULT,
         }
     }
+}
 
-    fn get_line<'a>(&mut self, line: &'a mut String) -> Result<OutputType<'a>, Error> {
-        loop {
-            self.stdout.read_line(line).map_err(Error::ReadError)?;
+impl ProgramOptions {
+    /// After programming, read back every section `mspdebug` programmed from
+    /// the ELF file and compare it to the file contents.
+    pub fn verify(mut self) -> Self {
+        self.flags |= ProgramFlags::VERIFY;
+        self
+    }
 
-            match line.chars().nth(0) {
-                Some(':') => return Ok(OutputType::Normal(&line[1..])),
-                Some('-') => return Ok(OutputType::Debug(&line[1..])),
-                Some('!') => {
-                    return Ok(OutputType::Error(self.get_error_severity(&line[1..])));
-                }
-                Some('\\') => {
-                    return Ok(OutputType::Shell(self.get_shell_type(&line[1..])?));
-                }
-                Some(un) => {
-                    return Err(Error::UnexpectedSigil(un));
+    /** Program the ELF file even if it doesn't fit the device, recording a
+    warning [`Diagnostic`] instead of failing with
+    [`BadInputReason::DoesNotFit`](crate::BadInputReason::DoesNotFit).
+    */
+    pub fn allow_misfit(mut self) -> Self {
+        self.flags |= ProgramFlags::ALLOW_MISFIT;
+        self
+    }
+}
+
+/// Keep the tail of `reader` (stderr) without blocking the child on a full
+/// pipe, passing each line to `cb` as it arrives.
+fn spawn_tail_reader<R>(reader: R, tail: Tail, mut cb: Option<StderrCallback>) -> JoinHandle<()>
+where
+    R: BufRead + Send + 'static,
+{
+    thread::spawn(move || {
+        for line in reader.lines() {
+            match line {
+                Ok(l) => {
+                    if let Some(cb) = cb.as_mut() {
+                        cb(&l);
+                    }
+                    tail.push(&l);
                 }
-                None => unreachable!(),
+                Err(_) => break,
             }
         }
-    }
+    })
+}
 
-    fn get_error_severity<'a>(&self, line: &'a str) -> ErrorSeverity<'a> {
-        match line {
-            line if line.starts_with("warning") => ErrorSeverity::Warning(line),
-            line => ErrorSeverity::Error(line),
+/// Read lines on a background thread, so that waiting for them can time out.
+/// An empty line is sent on EOF, after which the thread exits.
+fn spawn_reader<R>(mut reader: R) -> Receiver<io::Result<String>>
+where
+    R: BufRead + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || loop {
+        let mut line = String::new();
+        let res = reader.read_line(&mut line);
+        let done = !matches!(res, Ok(n) if n > 0);
+
+        if tx.send(res.map(|_| line)).is_err() || done {
+            break;
         }
+    });
+
+    rx
+}
+
+impl MspDebug {
+    pub(crate) fn new(
+        mut transport: Box<dyn Transport>,
+        recorder: Option<Recorder>,
+        mut cfg: Cfg,
+    ) -> Result<Self, Error> {
+        let stdout = transport.take_reader().map_err(Error::ReadError)?;
+        let stderr = transport.take_stderr();
+
+        let stderr_tail = Tail::new(cfg.stderr_lines);
+        let stderr_reader = stderr.map(|e| {
+            spawn_tail_reader(
+                io::BufReader::new(e),
+                stderr_tail.clone(),
+                cfg.on_stderr.take(),
+            )
+        });
+
+        Ok(Self {
+            transport,
+            stdout: spawn_reader(io::BufReader::new(stdout)),
+            stderr_reader,
+            need_drop: false,
+            proto: Protocol::new(&cfg, stderr_tail, recorder),
+        })
     }
 
-    fn get_shell_type(&self, line: &str) -> Result<ShellType, Error> {
-        match line {
-            line if line.eq("ready\n") || line.eq("ready\r\n") => Ok(ShellType::Ready),
-            line if line.eq("busy\n") || line.eq("busy\r\n") => Ok(ShellType::Busy),
-            line if line.eq("power-sample-us\n") || line.eq("power-sample-us\r\n") => {
-                Ok(ShellType::PowerSampleUs)
-            }
-            line if line.eq("power-samples\n") || line.eq("power-samples\r\n") => {
-                Ok(ShellType::PowerSamples)
+    fn get_line(&mut self, line: &mut String, deadline: Option<Instant>) -> Result<(), Error> {
+        let next = match deadline {
+            Some(d) => self
+                .stdout
+                .recv_timeout(d.saturating_duration_since(Instant::now())),
+            None => self
+                .stdout
+                .recv()
+                .map_err(|_| RecvTimeoutError::Disconnected),
+        };
+
+        *line = match next {
+            Ok(l) => l.map_err(Error::ReadError)?,
+            Err(RecvTimeoutError::Timeout) => {
+                // A hung programmer won't recover by itself; don't leave
+                // mspdebug (or its process group) behind.
+                let _ = self.transport.kill();
+                return Err(Error::Timeout(self.proto.timeout.unwrap_or_default()));
             }
-            line => Err(Error::UnexpectedShellMessage(line.to_owned())),
+            Err(RecvTimeoutError::Disconnected) => return Err(self.child_exited()),
+        };
+
+        if line.is_empty() {
+            return Err(self.child_exited());
+        }
+
+        Ok(())
+    }
+
+    /// `mspdebug` closed stdout; reap it and collect what it last said.
+    fn child_exited(&mut self) -> Error {
+        let status = self.transport.wait().ok().flatten();
+
+        // stderr is closed once mspdebug is gone, so this won't block for long.
+        if let Some(r) = self.stderr_reader.take() {
+            let _ = r.join();
         }
+
+        self.proto.child_exited(status)
     }
 
+    /// Block until `mspdebug` reports that it is ready to accept a command.
     pub fn wait_for_ready(&mut self) -> Result<(), Error> {
-        self.wait_for_ready_or_busy(WaitMode::Ready)
+        self.wait_for_ready_or_busy(WaitMode::Ready, None)
     }
 
+    /// Block until `mspdebug` reports that it started executing a command.
     pub fn wait_for_busy(&mut self) -> Result<(), Error> {
-        self.wait_for_ready_or_busy(WaitMode::Busy)
+        self.wait_for_ready_or_busy(WaitMode::Busy, None)
+    }
+
+    /// [`MspDebug::wait_for_ready`], giving up after `timeout`.
+    pub fn wait_for_ready_timeout(&mut self, timeout: Duration) -> Result<(), Error> {
+        self.with_timeout(Some(timeout), |msp| msp.wait_for_ready())
+    }
+
+    /// [`MspDebug::wait_for_busy`], giving up after `timeout`.
+    pub fn wait_for_busy_timeout(&mut self, timeout: Duration) -> Result<(), Error> {
+        self.with_timeout(Some(timeout), |msp| msp.wait_for_busy())
+    }
+
+    /// Recent lines `mspdebug` printed on stderr, oldest first. Empty unless
+    /// stderr is captured, see [`Cfg::capture_stderr`].
+    pub fn stderr(&self) -> Vec<String> {
+        self.proto.stderr_tail.lines()
+    }
+
+    /// Timeout applied to every wait for `mspdebug` to become ready or busy.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.proto.timeout
     }
 
-    fn wait_for_ready_or_busy(&mut self, mode: WaitMode) -> Result<(), Error> {
-        // Every command in this driver waits for ready at the beginning and
-        // end. Cache the value of ShellType, so we know what the last line was.
-        match self.last_shelltype {
-            Some(ShellType::Ready) if mode == WaitMode::Ready => return Ok(()),
-            Some(ShellType::Busy) if mode == WaitMode::Busy => return Ok(()),
-            _ => {}
+    /** Change the timeout applied to every wait for `mspdebug` to become ready
+    or busy. `None` waits forever.
+
+    When a wait times out, `mspdebug` is killed (along with its process group,
+    see [`Cfg::group`]) and [`Error::Timeout`] is returned. The session cannot
+    be used afterwards.
+    */
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.proto.timeout = timeout;
+    }
+
+    /** Run `f` with a different timeout, restoring the previous one afterwards.
+
+    ```no_run
+    # use std::time::Duration;
+    # use mspdebug_embedded::Cfg;
+    let mut msp = Cfg::new().timeout(Duration::from_secs(5)).run()?;
+    // Programming a large image takes longer than most commands.
+    msp.with_timeout(Some(Duration::from_secs(60)), |msp| msp.program("fw.elf"))?;
+    # Ok::<(), mspdebug_embedded::Error>(())
+    ```
+    */
+    pub fn with_timeout<T, F>(&mut self, timeout: Option<Duration>, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut Self) -> Result<T, Error>,
+    {
+        let prev = std::mem::replace(&mut self.proto.timeout, timeout);
+        let res = f(self);
+        self.proto.timeout = prev;
+
+        res
+    }
+
+    fn wait_for_ready_or_busy(
+        &mut self,
+        mode: WaitMode,
+        mut output: Option<&mut Vec<String>>,
+    ) -> Result<(), Error> {
+        if self.proto.waited(mode) {
+            return Ok(());
         }
 
+        let deadline = self.proto.timeout.map(|t| Instant::now() + t);
         let mut line = String::new();
 
         loop {
-            self.last_shelltype = None;
-            match self.get_line(&mut line)? {
-                OutputType::Shell(s) => match s {
-                    ShellType::Ready if mode == WaitMode::Ready => {
-                        self.last_shelltype = Some(ShellType::Ready);
-                        return Ok(());
-                    }
-                    ShellType::Busy if mode == WaitMode::Busy => {
-                        self.last_shelltype = Some(ShellType::Busy);
-                        return Ok(());
-                    }
-                    _stype => {}
-                },
-                OutputType::Error(ErrorSeverity::Warning(_w)) => { /* todo!() */ }
-                OutputType::Error(ErrorSeverity::Error(e)) => match e {
-                    e if e.starts_with("fet: FET returned error code")
-                        || e.starts_with("fet: command C_IDENT1 failed")
-                        || e.starts_with("fet: FET returned NAK") => {}
-                    e => {
-                        return Err(Error::CommsError(e.into()));
-                    }
-                },
-                OutputType::Normal(n) if n.starts_with("Device: ") && self.device.is_none() => {
-                    self.device = Some(n[8..].trim_end().to_owned());
-                }
-                _ => {}
+            self.get_line(&mut line, deadline)?;
+            if self.proto.feed(&line, mode, output.as_deref_mut())? {
+                return Ok(());
             }
-
-            line.clear();
         }
     }
 
-    pub fn program<F>(&mut self, filename: F) -> Result<(), Error>
+    /// Warnings and ignored errors collected since the last call to
+    /// [`MspDebug::drain_diagnostics`].
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.proto.diagnostics
+    }
+
+    /// Remove and return all collected warnings and ignored errors.
+    pub fn drain_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.proto.diagnostics)
+    }
+
+    /** Call `cb` for every warning or ignored error as soon as it is read.
+
+    Diagnostics are still collected for [`MspDebug::drain_diagnostics`]
+    when a callback is set.
+    */
+    pub fn on_diagnostic<F>(&mut self, cb: F)
     where
-        F: AsRef<Path>,
+        F: FnMut(&Diagnostic) + Send + 'static,
     {
-        if self.cfg.group {
-            return Err(Error::ExpectedNoProcessGroup);
-        }
+        self.proto.on_diagnostic = Some(Box::new(cb));
+    }
 
-        let elf = Self::validate_elf(&filename)?;
-        if let Some((origin, length, sector_size)) = self.validate_infomem(elf)? {
-            self.wait_for_ready()?;
-            write!(
-                self,
-                ":erase segrange {} {} {}\n",
-                origin, length, sector_size
-            )
-            .map_err(|e| Error::WriteError(e))?;
-            self.wait_for_busy()?;
-            self.wait_for_ready()?;
+    /** Receive an [`Event`] for every line `mspdebug` prints from now on.
+
+    Lines are only read while a command runs, so events arrive as the
+    command progresses, e.g. to mirror the `mspdebug` console from another
+    thread. Dropping the receiver unsubscribes.
+
+    ```no_run
+    # use std::thread;
+    # use mspdebug_embedded::{Cfg, Event};
+    let mut msp = Cfg::new().run()?;
+    let events = msp.subscribe();
+
+    thread::spawn(move || {
+        for ev in events {
+            if let Event::Output(line) = ev {
+                println!("{}", line);
+            }
         }
+    });
 
+    msp.program("fw.elf")?;
+    # Ok::<(), mspdebug_embedded::Error>(())
+    ```
+    */
+    pub fn subscribe(&mut self) -> Receiver<Event> {
+        self.proto.subscribers.subscribe()
+    }
+
+    /// Wait for `mspdebug` to become ready, send `cmd`, and wait for
+    /// `mspdebug` to acknowledge it.
+    fn start_command(&mut self, cmd: &str) -> Result<(), Error> {
         self.wait_for_ready()?;
-        write!(self, ":prog {}\n", filename.as_ref().display())
-            .map_err(|e| Error::WriteError(e))?;
-        self.wait_for_busy()?;
-        self.wait_for_ready()?;
+        writeln!(self, ":{}", cmd).map_err(Error::WriteError)?;
+        self.wait_for_busy()
+    }
 
-        Ok(())
+    /// Do what `step` asks of `mspdebug`, and return the output collected.
+    fn exchange(&mut self, step: Step) -> Reply {
+        let mut output = Vec::new();
+
+        match step {
+            Step::Ready => self.wait_for_ready()?,
+            Step::Start(cmd) => self.start_command(&cmd)?,
+            Step::Command(cmd) => {
+                self.start_command(&cmd)?;
+                self.wait_for_ready_or_busy(WaitMode::Ready, Some(&mut output))?;
+            }
+            // Lines printed in the meantime are read once the target halts.
+            Step::Pause(duration) => thread::sleep(duration),
+            Step::Stop(interrupt) => {
+                if interrupt {
+                    writeln!(self, "\\break").map_err(Error::WriteError)?;
+                }
+                self.wait_for_ready_or_busy(WaitMode::Ready, Some(&mut output))?;
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Run `op` to completion.
+    fn perform<T>(&mut self, mut op: Op<T>) -> Result<T, Error> {
+        loop {
+            op = match op {
+                Op::Done(val) => return Ok(val),
+                Op::Next(next) => next(&mut self.proto)?,
+                Op::Step(step, then) => {
+                    let reply = self.exchange(step);
+                    then(&mut self.proto, reply)?
+                }
+            };
+        }
+    }
+
+    /** Run an arbitrary `mspdebug` command and return its normal output.
+
+    The command is given without the embedded-mode `:` sigil, e.g.
+    `"md 0x200 16"`. Each returned line has its sigil and line ending
+    stripped. Debug output is discarded, and the first error `mspdebug`
+    reports is returned as [`Error::CommsError`].
+    */
+    pub fn command(&mut self, cmd: &str) -> Result<Vec<String>, Error> {
+        self.perform(ops::command(cmd.to_owned()))
+    }
+
+    /// Name of the attached device, as reported by `mspdebug` on startup.
+    ///
+    /// Returns `None` until `mspdebug` has finished opening the device, or if
+    /// the driver does not report one (e.g. the simulator).
+    pub fn device(&self) -> Option<&str> {
+        self.proto.device.as_deref()
+    }
+
+    /// Reset the target and halt the CPU (`reset`).
+    pub fn reset(&mut self) -> Result<(), Error> {
+        self.perform(ops::reset())
+    }
+
+    /// Erase main memory of the target (`erase`).
+    ///
+    /// Information Memory is left untouched; see [`MspDebug::erase_segrange`].
+    pub fn erase(&mut self) -> Result<(), Error> {
+        self.perform(ops::erase())
+    }
+
+    /// Erase `length` bytes starting at `origin`, one `sector_size`-sized
+    /// segment at a time (`erase segrange`).
+    pub fn erase_segrange(
+        &mut self,
+        origin: u16,
+        length: u16,
+        sector_size: u16,
+    ) -> Result<(), Error> {
+        self.perform(ops::erase_segrange(origin, length, sector_size))
+    }
+
+    /// Read Information Memory segment A, which holds the factory calibration
+    /// on most devices.
+    pub fn segment_a(&mut self) -> Result<Vec<u8>, Error> {
+        self.perform(ops::segment_a())
+    }
+
+    /// Erase Information Memory segment A and write `data`, e.g. calibration
+    /// read with [`MspDebug::segment_a`] earlier, to it.
+    pub fn write_segment_a(&mut self, data: &[u8]) -> Result<(), Error> {
+        self.perform(ops::write_segment_a(data.to_vec()))
+    }
+
+    /// Write back the segment A backup saved for this device and programmer;
+    /// see [`Cfg::calibration_backups`].
+    pub fn restore_segment_a(&mut self) -> Result<(), Error> {
+        self.perform(ops::restore_segment_a())
+    }
+
+    /** Read and decode the factory calibration of the device: segment A on
+    2xx parts, or the device descriptor table on 5xx, 6xx and FRAM parts.
+
+    A table whose checksum doesn't match is reported as
+    [`Error::BadChecksum`], e.g. after segment A was erased.
+    */
+    pub fn calibration(&mut self) -> Result<Calibration, Error> {
+        self.perform(ops::calibration())
+    }
+
+    /** Read `len` bytes of target memory starting at `addr` (`md`).
+
+    Large reads are split into several `md` commands. The address of every
+    line of the hex dump is checked, so a short or out-of-order dump is
+    reported as [`Error::UnexpectedOutput`] rather than returning shifted data.
+    */
+    pub fn read_memory(&mut self, addr: u32, len: usize) -> Result<Vec<u8>, Error> {
+        self.perform(ops::read_memory(addr, len))
+    }
+
+    /** Write `data` to target memory starting at `addr` (`mw`).
+
+    Large payloads are split into several `mw` commands. If `mspdebug`
+    rejects a write, [`Error::MemoryWriteError`] holds the address of the
+    chunk that failed; chunks before it have already been written.
+    */
+    pub fn write_memory(&mut self, addr: u32, data: &[u8]) -> Result<(), Error> {
+        self.perform(ops::write_memory(addr, data.to_vec()))
+    }
+
+    /// Write a single 16-bit little-endian word to target memory at `addr`.
+    pub fn write_word(&mut self, addr: u32, word: u16) -> Result<(), Error> {
+        self.write_memory(addr, &word.to_le_bytes())
+    }
+
+    /** Let the target run freely until it is halted or stops on its own (`run`).
+
+    Returns as soon as `mspdebug` acknowledges the command. The returned
+    [`Running`] handle stops the target; dropping it without calling
+    [`Running::halt`] or [`Running::wait`] halts the target as well.
+    */
+    pub fn run(&mut self) -> Result<Running<'_>, Error> {
+        self.perform(ops::run())?;
+
+        Ok(Running { msp: self })
+    }
+
+    /** Halt the target and report where it stopped.
+
+    If a [`MspDebug::run`] is in progress, it is interrupted with `\break`.
+    Otherwise the CPU is already halted (`mspdebug` halts it between
+    commands), and only the current registers are read.
+    */
+    pub fn halt(&mut self) -> Result<Stop, Error> {
+        self.perform(ops::halt())
+    }
+
+    /** Run until the CPU reaches `addr`, using a temporary breakpoint.
+
+    The breakpoint is removed again before returning. If that fails, the stop
+    is still returned and the failure is recorded as a warning [`Diagnostic`].
+    See [`symbol_address`](crate::symbol_address) to get `addr` from an ELF
+    file.
+    */
+    pub fn run_until(&mut self, addr: u32) -> Result<Stop, Error> {
+        self.perform(ops::run_until(addr))
+    }
+
+    /** Run the target for `duration` while sampling its current, then halt
+    it again.
+
+    Only drivers with [`Capabilities::power_profiling`](crate::Capabilities)
+    (`tilib` and `ezfet`, on devices with EnergyTrace) send power samples;
+    [`Error::NoPowerSamples`] is returned if `mspdebug` sent none.
+
+    ```no_run
+    # use std::fs::File;
+    # use std::time::Duration;
+    # use mspdebug_embedded::{Cfg, TargetDriver};
+    let mut msp = Cfg::new().driver(TargetDriver::EzFet).run()?;
+    let profile = msp.profile_power(Duration::from_secs(10))?;
+
+    println!("{:.1} uA average", profile.average_current_ua());
+    profile.write_csv(File::create("power.csv").unwrap()).unwrap();
+    # Ok::<(), mspdebug_embedded::Error>(())
+    ```
+    */
+    pub fn profile_power(&mut self, duration: Duration) -> Result<PowerProfile, Error> {
+        self.perform(ops::profile_power(duration))
+    }
+
+    /// Single-step the CPU `count` instructions and return the registers
+    /// afterwards (`step`).
+    pub fn step(&mut self, count: u32) -> Result<Registers, Error> {
+        self.perform(ops::step(count))
+    }
+
+    /// Set a breakpoint at `addr` and return its index (`setbreak`).
+    pub fn set_breakpoint(&mut self, addr: u32) -> Result<usize, Error> {
+        self.set_watchpoint(addr, BreakpointKind::Break)
+    }
+
+    /** Set a breakpoint or watchpoint of the given kind at `addr` and return
+    its index (`setbreak`, `setwatch`, `setwatchr` or `setwatchw`).
+    */
+    pub fn set_watchpoint(&mut self, addr: u32, kind: BreakpointKind) -> Result<usize, Error> {
+        self.perform(ops::set_watchpoint(addr, kind))
+    }
+
+    /// Delete the breakpoint with the given index (`delbreak`).
+    pub fn delete_breakpoint(&mut self, index: usize) -> Result<(), Error> {
+        self.perform(ops::delete_breakpoint(index))
+    }
+
+    /// Delete all breakpoints (`delbreak`).
+    pub fn clear_breakpoints(&mut self) -> Result<(), Error> {
+        self.perform(ops::clear_breakpoints())
+    }
+
+    /// List breakpoints that are currently set (`break`).
+    pub fn breakpoints(&mut self) -> Result<Vec<Breakpoint>, Error> {
+        self.perform(ops::breakpoints())
+    }
+
+    /// Read all CPU registers (`regs`).
+    pub fn registers(&mut self) -> Result<Registers, Error> {
+        self.perform(ops::registers())
+    }
+
+    /// Set a single CPU register (`set`).
+    pub fn set_register(&mut self, reg: Register, value: u32) -> Result<(), Error> {
+        self.perform(ops::set_register(reg, value))
+    }
+
+    /** Program the target with an ELF file (`prog`).
+
+    If the ELF file places data in Information Memory segments D through B,
+    those segments are erased first. Segment A is never erased.
+    */
+    pub fn program<F>(&mut self, filename: F) -> Result<(), Error>
+    where
+        F: AsRef<Path>,
+    {
+        self.program_with(filename, ProgramOptions::default())
+    }
+
+    /// Program the target with an ELF file, like [`MspDebug::program`], using
+    /// the given options.
+    pub fn program_with<F>(&mut self, filename: F, opts: ProgramOptions) -> Result<(), Error>
+    where
+        F: AsRef<Path>,
+    {
+        self.perform(ops::program(
+            filename.as_ref().to_owned(),
+            opts.flags.contains(ProgramFlags::ALLOW_MISFIT),
+            opts.flags.contains(ProgramFlags::VERIFY),
+        ))
     }
 
     /** Run `mspdebug` in `gdb` server mode and spawn a `msp430-elf-gdb` session.
@@ -251,134 +712,86 @@ impl MspDebug {
     where
         F: AsRef<Path>,
     {
-        if !self.cfg.group {
-            return Err(Error::ExpectedProcessGroup);
-        }
-
-        let elf = Self::validate_elf(&filename)?;
-        let im = self.validate_infomem(elf)?;
-
-        ctrlc::set_handler(move || {}).map_err(|e| Error::CtrlCError(e))?;
-        self.wait_for_ready()?;
-        write!(self, ":gdb {}\n", cfg.port).map_err(|e| Error::WriteError(e))?;
-        self.wait_for_busy()?;
+        let args = self.perform(ops::start_gdb(filename.as_ref().to_owned(), cfg))?;
 
         // FIXME: Between here and gdb invocation, if this function panics,
         // mspdebug will not exit by itself. Figure out why.
         // Might be a small race here too (between wait_for_busy returning and
         // need_drop being set)?
         self.need_drop = true;
-        let fn_string = filename.as_ref().to_string_lossy();
-        let mut args = Vec::new();
-
-        if cfg.flags.contains(GdbConfigFlags::QUIET) {
-            args.push("-q");
-        }
-
-        let target_str = format!("target remote localhost:{}", cfg.port);
-        args.extend(["-ex", &target_str]);
-
-        if cfg.flags.contains(GdbConfigFlags::ERASE) {
-            args.extend(["-ex", "monitor erase"]);
-        }
-
-        let erase_infomem_str: String;
-        if cfg.flags.contains(GdbConfigFlags::ERASE_INFOMEM) && im.is_some() {
-            let (origin, length, sector_size) = im.unwrap();
-            erase_infomem_str = format!(
-                "monitor erase segrange {} {} {}",
-                origin, length, sector_size
-            );
-            args.extend(["-ex", &erase_infomem_str])
-        }
-
-        if cfg.flags.contains(GdbConfigFlags::LOAD) {
-            args.extend(["-ex", "load"]);
-        }
-
-        args.extend(["-ex", "monitor reset"]);
-
-        for arg in cfg.extra_args.iter() {
-            args.extend(["-ex", arg])
-        }
-
-        args.push(&fn_string);
 
         let mut gdb = Command::new("msp430-elf-gdb")
             .args(&args)
             .spawn()
-            .map_err(|e| Error::SpawnError(e))?;
+            .map_err(Error::SpawnError)?;
 
         self.need_drop = false;
         // When gdb exits, mspdebug will too.
-        let exit = gdb.wait().map_err(|e| Error::GdbError(e))?;
+        let exit = gdb.wait().map_err(Error::GdbError)?;
 
         Ok(exit)
     }
+}
 
-    fn validate_elf<F>(filename: F) -> Result<ElfStream<LittleEndian, File>, Error>
-    where
-        F: AsRef<Path>,
-    {
-        let fp = File::open(&filename).map_err(|e| Error::BadInput(BadInputReason::IoError(e)))?;
-        let elf = ElfStream::open_stream(fp)
-            .map_err(|p| Error::BadInput(BadInputReason::ElfParseError(p)))?;
-
-        Ok(elf)
-    }
-
-    fn validate_infomem(
-        &mut self,
-        elf: ElfStream<LittleEndian, File>,
-    ) -> Result<Option<(u16, u16, u16)>, Error> {
-        // In case no "wait_for_ready" was run before this point, device info will
-        // be printed out by mspdebug/parsed by us before wait_for_ready() returns.
-        self.wait_for_ready()?;
-        if self.cfg.driver != TargetDriver::Sim {
-            let device = self.device.clone().ok_or(Error::NoDevice)?;
+/** Handle to a target started with [`MspDebug::run`].
 
-            let (origin, mut length, sector_size) = INFOMEM_MAP
-                .get(device.as_ref())
-                .cloned()
-                .flatten()
-                .ok_or(Error::UnknownDevice(device.to_string()))?;
+The session is borrowed for as long as the target runs; no other commands can
+be sent until it is halted.
+*/
+pub struct Running<'a> {
+    msp: &'a mut MspDebug,
+}
 
-            let im_range = origin.into()..(origin + length).into();
+impl Running<'_> {
+    /// Interrupt the target and return where it stopped.
+    pub fn halt(self) -> Result<Stop, Error> {
+        self.msp.halt()
+    }
 
-            for hdr in elf.section_headers() {
-                if im_range.contains(&hdr.sh_addr) {
-                    length -= sector_size; /* Sector A, the last sector, may contain
-                                           calibration info. Don't overwrite it. */
+    /// Block until the target stops on its own, e.g. at a breakpoint.
+    pub fn wait(self) -> Result<Stop, Error> {
+        self.msp.perform(ops::finish_run(false))
+    }
+}
 
-                    return Ok(Some((origin, length, sector_size)));
-                }
-            }
+impl Drop for Running<'_> {
+    fn drop(&mut self) {
+        if self.msp.proto.running {
+            let _ = self.msp.halt();
         }
-
-        Ok(None)
     }
 }
 
-impl io::Read for MspDebug {
-    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        self.stdout.read(buf)
-    }
+/// Information Memory of `device`: origin, length and segment size.
+pub(crate) fn infomem_layout(device: Option<&str>) -> Result<(u16, u16, u16), Error> {
+    let device = device.ok_or(Error::NoDevice)?;
+
+    INFOMEM_MAP
+        .get(device)
+        .cloned()
+        .flatten()
+        .ok_or(Error::UnknownDevice(device.to_string()))
 }
 
 impl io::Write for MspDebug {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.stdin.write(buf)
+        let n = self.transport.write(buf)?;
+        self.proto.sent(&buf[..n]);
+
+        Ok(n)
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        self.stdin.flush()
+        self.transport.flush()
     }
 }
 
 impl Drop for MspDebug {
     fn drop(&mut self) {
         if self.need_drop {
-            self.child.kill().unwrap()
+            self.transport.kill().unwrap()
         }
+
+        let _ = self.transport.close();
     }
 }
@@ -1,10 +1,13 @@
 use std::error;
 use std::fmt;
 use std::io;
+use std::process::ExitStatus;
+use std::time::Duration;
 
-use ctrlc;
 use elf::parse::ParseError;
 
+use crate::TargetDriver;
+
 #[derive(Debug)]
 pub enum Error {
     SpawnError(io::Error),
@@ -16,11 +19,47 @@ pub enum Error {
     WriteError(io::Error),
     UnexpectedSigil(char),
     UnexpectedShellMessage(String),
-    CommsError(String),
+    CommsError {
+        msg: String,
+        /// Recent stderr output, see [`Cfg::capture_stderr`](crate::Cfg::capture_stderr).
+        stderr: Vec<String>,
+    },
     CtrlCError(ctrlc::Error),
     GdbError(io::Error),
     NoDevice,
     UnknownDevice(String),
+    UnexpectedOutput(String),
+    MemoryWriteError {
+        addr: u32,
+        msg: String,
+    },
+    VerifyFailed {
+        addr: u32,
+        expected: u8,
+        actual: u8,
+    },
+    Timeout(Duration),
+    UnsupportedOption {
+        driver: TargetDriver,
+        option: &'static str,
+    },
+    Unsupported {
+        driver: TargetDriver,
+        operation: &'static str,
+    },
+    ChildExited {
+        status: Option<ExitStatus>,
+        last_output: OutputTail,
+    },
+    RecordError(io::Error),
+    ConnectError(io::Error),
+    NoPowerSamples,
+    CalibrationBackupError(io::Error),
+    BadChecksum {
+        addr: u32,
+        expected: u16,
+        actual: u16,
+    },
 }
 
 impl fmt::Display for Error {
@@ -54,7 +93,7 @@ impl fmt::Display for Error {
             Error::UnexpectedShellMessage(msg) => {
                 write!(f, "unexpected shell message, expected 'ready', 'busy', 'power-sample-us', or 'power-samples', got {}", msg)
             }
-            Error::CommsError(msg) => {
+            Error::CommsError { msg, .. } => {
                 write!(f, "mspdebug could not communicate with the device {}", msg)
             }
             Error::CtrlCError(e) => {
@@ -73,6 +112,69 @@ impl fmt::Display for Error {
             Error::UnknownDevice(d) => {
                 write!(f, "device known by mspdebug but not this crate, got {}", d)
             }
+            Error::UnexpectedOutput(line) => {
+                write!(f, "could not parse mspdebug output, got {}", line)
+            }
+            Error::MemoryWriteError { addr, msg } => {
+                write!(f, "could not write memory at 0x{:04x}: {}", addr, msg)
+            }
+            Error::VerifyFailed {
+                addr,
+                expected,
+                actual,
+            } => {
+                write!(
+                    f,
+                    "verification failed at 0x{:04x}, expected 0x{:02x}, got 0x{:02x}",
+                    addr, expected, actual
+                )
+            }
+            Error::Timeout(t) => {
+                write!(f, "mspdebug did not respond within {:?}", t)
+            }
+            Error::UnsupportedOption { driver, option } => {
+                write!(
+                    f,
+                    "mspdebug driver {} does not take option {}",
+                    driver.as_ref(),
+                    option
+                )
+            }
+            Error::Unsupported { driver, operation } => {
+                write!(
+                    f,
+                    "mspdebug driver {} cannot {}",
+                    driver.as_ref(),
+                    operation
+                )
+            }
+            Error::ChildExited { status, .. } => match status {
+                Some(s) => write!(f, "mspdebug exited unexpectedly ({})", s),
+                None => write!(f, "mspdebug exited unexpectedly"),
+            },
+            Error::RecordError(_) => write!(f, "could not create session transcript"),
+            Error::ConnectError(_) => write!(f, "could not connect to mspdebug"),
+            Error::NoPowerSamples => write!(
+                f,
+                "mspdebug did not send power samples; does the device support EnergyTrace?"
+            ),
+            Error::CalibrationBackupError(_) => {
+                write!(
+                    f,
+                    "could not read or write Information Memory segment A backup"
+                )
+            }
+            Error::BadChecksum {
+                addr,
+                expected,
+                actual,
+            } => {
+                write!(
+                    f,
+                    "bad checksum for calibration table at 0x{:04x}, expected 0x{:04x}, got 0x{:04x}",
+                    addr, expected, actual
+                )
+            }
         }
     }
 }
@@ -83,7 +185,10 @@ impl error::Error for Error {
             Error::SpawnError(io)
             | Error::ReadError(io)
             | Error::WriteError(io)
-            | Error::GdbError(io) => Some(io),
+            | Error::GdbError(io)
+            | Error::RecordError(io)
+            | Error::ConnectError(io)
+            | Error::CalibrationBackupError(io) => Some(io),
             Error::CtrlCError(e) => Some(e),
             Error::BadInput(r) => Some(r),
             Error::ExpectedProcessGroup
@@ -91,17 +196,56 @@ impl error::Error for Error {
             | Error::StreamError(_)
             | Error::UnexpectedSigil(_)
             | Error::UnexpectedShellMessage(_)
-            | Error::CommsError(_)
+            | Error::CommsError { .. }
             | Error::NoDevice
-            | Error::UnknownDevice(_) => None,
+            | Error::UnknownDevice(_)
+            | Error::UnexpectedOutput(_)
+            | Error::MemoryWriteError { .. }
+            | Error::VerifyFailed { .. }
+            | Error::Timeout(_)
+            | Error::UnsupportedOption { .. }
+            | Error::Unsupported { .. }
+            | Error::ChildExited { .. }
+            | Error::NoPowerSamples
+            | Error::BadChecksum { .. } => None,
         }
     }
 }
 
+/// The last lines `mspdebug` printed before an error, oldest first.
+#[derive(Clone, Debug, Default)]
+pub struct OutputTail {
+    /// Embedded-mode output, sigils included.
+    pub stdout: Vec<String>,
+    pub stderr: Vec<String>,
+}
+
 #[derive(Debug)]
 pub enum BadInputReason {
     IoError(io::Error),
     ElfParseError(ParseError),
+    /// The ELF file is not for the MSP430; holds its `e_machine`.
+    WrongMachine(u16),
+    /// A section to be programmed does not fit the memory map of the device.
+    DoesNotFit {
+        device: String,
+        section: String,
+        /// First address of the section that does not fit.
+        addr: u32,
+        reason: FitReason,
+    },
+}
+
+/// Why a section does not fit the device, see [`BadInputReason::DoesNotFit`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FitReason {
+    /// Not in main memory, Information Memory or RAM.
+    OutsideMemory,
+    /// Overlaps the bootstrap loader.
+    Bsl,
+    /// Where a larger part of the family would have main memory; the file is
+    /// probably built for another variant.
+    TooLarge,
 }
 
 impl fmt::Display for BadInputReason {
@@ -109,6 +253,27 @@ impl fmt::Display for BadInputReason {
         match self {
             BadInputReason::IoError(_) => write!(f, "error opening or reading input file"),
             BadInputReason::ElfParseError(_) => write!(f, "input file was not a valid ELF file"),
+            BadInputReason::WrongMachine(m) => {
+                write!(f, "input file is not an MSP430 ELF file (machine {})", m)
+            }
+            BadInputReason::DoesNotFit {
+                device,
+                section,
+                addr,
+                reason,
+            } => {
+                let why = match reason {
+                    FitReason::OutsideMemory => "outside flash, FRAM, Information Memory and RAM",
+                    FitReason::Bsl => "overlaps the bootstrap loader",
+                    FitReason::TooLarge => "beyond the memory of this part",
+                };
+
+                write!(
+                    f,
+                    "section {} at 0x{:x} does not fit {}: {}",
+                    section, addr, device, why
+                )
+            }
         }
     }
 }
@@ -118,6 +283,7 @@ impl error::Error for BadInputReason {
         match self {
             BadInputReason::IoError(io) => Some(io),
             BadInputReason::ElfParseError(elf) => Some(elf),
+            BadInputReason::WrongMachine(_) | BadInputReason::DoesNotFit { .. } => None,
         }
     }
 }
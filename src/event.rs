@@ -0,0 +1,62 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use crate::protocol::{ErrorSeverity, OutputType, ShellType};
+
+/** A line `mspdebug` printed in embedded mode, as seen by
+[`MspDebug::subscribe`](crate::MspDebug::subscribe).
+
+Messages have their sigil and line ending stripped.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// Normal output (`:`), e.g. a line of a memory dump.
+    Output(String),
+    /// Debug output (`-`).
+    Debug(String),
+    /// A warning (`!warning: ...`).
+    Warning(String),
+    /** Any other error (`!`). Whether it fails the current command is up to
+    the session; errors `mspdebug` recovers from by itself are reported as
+    [`Diagnostic`](crate::Diagnostic)s instead.
+    */
+    Error(String),
+    /// A change of shell state (`\`).
+    Shell(ShellType),
+}
+
+impl From<&OutputType<'_>> for Event {
+    fn from(out: &OutputType<'_>) -> Self {
+        let msg = |m: &str| m.trim_end_matches(['\r', '\n']).to_owned();
+
+        match out {
+            OutputType::Normal(n) => Event::Output(msg(n)),
+            OutputType::Debug(d) => Event::Debug(msg(d)),
+            OutputType::Error(ErrorSeverity::Warning(w)) => Event::Warning(msg(w)),
+            OutputType::Error(ErrorSeverity::Error(e)) => Event::Error(msg(e)),
+            OutputType::Shell(s) => Event::Shell(s.clone()),
+        }
+    }
+}
+
+/// Channels handed out by `subscribe`. Subscribers that hung up are dropped
+/// the next time an event is published.
+#[derive(Default)]
+pub(crate) struct Subscribers(Vec<Sender<Event>>);
+
+impl Subscribers {
+    pub(crate) fn subscribe(&mut self) -> Receiver<Event> {
+        let (tx, rx) = mpsc::channel();
+        self.0.push(tx);
+
+        rx
+    }
+
+    pub(crate) fn publish(&mut self, out: &OutputType<'_>) {
+        if self.0.is_empty() {
+            return;
+        }
+
+        let event = Event::from(out);
+        self.0.retain(|tx| tx.send(event.clone()).is_ok());
+    }
+}
@@ -0,0 +1,242 @@
+use std::fs::File;
+use std::ops::Range;
+use std::path::Path;
+
+use elf::abi::{EM_MSP430, PT_LOAD, SHF_ALLOC, SHT_PROGBITS};
+use elf::endian::LittleEndian;
+use elf::ElfStream;
+
+use crate::device::DeviceInfo;
+use crate::driver::infomem_layout;
+use crate::error::BadInputReason;
+use crate::{Connection, Error, TargetDriver};
+
+/** An ELF file about to be programmed, as `mspdebug` will load it.
+
+Reading and checking the file doesn't involve `mspdebug`, so this is shared by
+[`MspDebug`](crate::MspDebug) and the async session; they only differ in how
+they wait for the device name that [`Image::prepare`] needs.
+*/
+pub(crate) struct Image {
+    /// Allocated `PROGBITS` sections.
+    sections: Vec<Section>,
+}
+
+/// Information Memory to erase with `erase segrange`: origin, length and
+/// segment size.
+pub(crate) type EraseRange = (u16, u16, u16);
+
+/// A section `mspdebug` programs.
+pub(crate) struct Section {
+    name: String,
+    /// Load address, taken from the `PT_LOAD` segment holding the section.
+    pub(crate) addr: u32,
+    pub(crate) data: Vec<u8>,
+}
+
+impl Section {
+    fn range(&self) -> Range<u32> {
+        self.addr..self.addr + self.data.len() as u32
+    }
+}
+
+/// Open an ELF file, checking that it is for the MSP430.
+pub(crate) fn open_elf<F>(filename: F) -> Result<ElfStream<LittleEndian, File>, Error>
+where
+    F: AsRef<Path>,
+{
+    let fp = File::open(&filename).map_err(|e| Error::BadInput(BadInputReason::IoError(e)))?;
+    let elf: ElfStream<LittleEndian, File> = ElfStream::open_stream(fp)
+        .map_err(|p| Error::BadInput(BadInputReason::ElfParseError(p)))?;
+
+    if elf.ehdr.e_machine != EM_MSP430 {
+        return Err(Error::BadInput(BadInputReason::WrongMachine(
+            elf.ehdr.e_machine,
+        )));
+    }
+
+    Ok(elf)
+}
+
+impl Image {
+    pub(crate) fn open<F>(filename: F) -> Result<Self, Error>
+    where
+        F: AsRef<Path>,
+    {
+        let mut elf = open_elf(&filename)?;
+        let loads: Vec<_> = elf
+            .segments()
+            .iter()
+            .filter(|p| p.p_type == PT_LOAD)
+            .copied()
+            .collect();
+
+        let (shdrs, strtab) = elf
+            .section_headers_with_strtab()
+            .map_err(|p| Error::BadInput(BadInputReason::ElfParseError(p)))?;
+        let programmed: Vec<_> = shdrs
+            .iter()
+            .filter(|s| s.sh_type == SHT_PROGBITS && s.sh_flags & u64::from(SHF_ALLOC) != 0)
+            .filter(|s| s.sh_size > 0)
+            .map(|shdr| {
+                let name = strtab
+                    .as_ref()
+                    .and_then(|t| t.get(shdr.sh_name as usize).ok())
+                    .unwrap_or("?");
+
+                (name.to_owned(), *shdr)
+            })
+            .collect();
+
+        let mut sections = Vec::new();
+        for (name, shdr) in programmed {
+            let addr = loads
+                .iter()
+                .find(|p| (p.p_offset..p.p_offset + p.p_filesz).contains(&shdr.sh_offset))
+                .map_or(shdr.sh_addr, |p| p.p_paddr + (shdr.sh_offset - p.p_offset));
+            let (data, _) = elf
+                .section_data(&shdr)
+                .map_err(|p| Error::BadInput(BadInputReason::ElfParseError(p)))?;
+
+            sections.push(Section {
+                name,
+                addr: addr as u32,
+                data: data.to_vec(),
+            });
+        }
+
+        Ok(Self { sections })
+    }
+
+    /** Check that the image fits `device`, and return the Information Memory
+    to erase before programming it (see [`Image::infomem_ranges`]) along with
+    a warning, if any.
+
+    A section that doesn't fit is an error, unless `allow_misfit` is set;
+    then it is reported as a warning, as is a device the image can't be
+    checked against.
+    */
+    pub(crate) fn prepare(
+        &self,
+        driver: TargetDriver,
+        device: Option<&str>,
+        allow_misfit: bool,
+    ) -> Result<(Vec<EraseRange>, Option<String>), Error> {
+        let warning = match self.check_fit(driver, device) {
+            Err(Error::BadInput(reason)) if allow_misfit => Some(reason.to_string()),
+            res => res?,
+        };
+
+        Ok((self.infomem_ranges(driver, device)?, warning))
+    }
+
+    /// Every section `mspdebug` programs, with the address it is loaded at.
+    /// Headers and padding in loadable segments aren't part of any of them.
+    pub(crate) fn into_sections(self) -> Vec<Section> {
+        self.sections
+    }
+
+    /** Check that every section `mspdebug` programs fits the memory map of
+    `device`.
+
+    `mspdebug` loads allocated `PROGBITS` sections at their load address.
+    Simulators aren't checked; for devices missing from [`DeviceInfo`]'s
+    table, a warning is returned instead.
+    */
+    fn check_fit(
+        &self,
+        driver: TargetDriver,
+        device: Option<&str>,
+    ) -> Result<Option<String>, Error> {
+        if driver.capabilities().connection == Connection::Simulator {
+            return Ok(None);
+        }
+
+        let Some(device) = device else {
+            return Ok(Some(
+                "no device reported; not checking that the ELF file fits".to_owned(),
+            ));
+        };
+        let Some(info) = DeviceInfo::lookup(device) else {
+            return Ok(Some(format!(
+                "no memory map for {}; not checking that the ELF file fits",
+                device
+            )));
+        };
+
+        for s in &self.sections {
+            if let Some((addr, reason)) = info.misfit(s.range()) {
+                return Err(Error::BadInput(BadInputReason::DoesNotFit {
+                    device: device.to_owned(),
+                    section: s.name.clone(),
+                    addr,
+                    reason,
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /** The Information Memory to erase before programming, as
+    `(origin, length, sector_size)` ranges for `erase segrange`.
+
+    Only segments that a programmed section overlaps are erased, the same
+    sections [`Image::check_fit`] checks and verification reads back; adjacent
+    ones are merged into one range. Segment A, the last segment, is
+    left out since it may contain calibration info.
+    */
+    fn infomem_ranges(
+        &self,
+        driver: TargetDriver,
+        device: Option<&str>,
+    ) -> Result<Vec<EraseRange>, Error> {
+        let caps = driver.capabilities();
+        if caps.connection == Connection::Simulator {
+            return Ok(Vec::new());
+        }
+
+        let (origin, length, sector_size) = infomem_layout(device)?;
+        // Segment A is the last segment; some parts have no others.
+        let segments = usize::from(length / sector_size);
+        let mut touched = vec![false; segments.saturating_sub(1)];
+
+        for load in self.sections.iter().map(Section::range) {
+            for (i, t) in touched.iter_mut().enumerate() {
+                let seg_start = u32::from(origin) + i as u32 * u32::from(sector_size);
+                *t |= load.start < seg_start + u32::from(sector_size) && seg_start < load.end;
+            }
+        }
+
+        let mut ranges: Vec<EraseRange> = Vec::new();
+        for (i, _) in touched.iter().enumerate().filter(|(_, &t)| t) {
+            let addr = origin + i as u16 * sector_size;
+
+            match ranges.last_mut() {
+                Some((o, l, _)) if *o + *l == addr => *l += sector_size,
+                _ => ranges.push((addr, sector_size, sector_size)),
+            }
+        }
+
+        if !ranges.is_empty() && !caps.erase_infomem {
+            return Err(Error::Unsupported {
+                driver,
+                operation: "erase Information Memory",
+            });
+        }
+
+        Ok(ranges)
+    }
+}
+
+/// Compare memory read back from `addr` against what was programmed there.
+pub(crate) fn compare(addr: u32, expected: &[u8], actual: &[u8]) -> Result<(), Error> {
+    match (0..expected.len()).find(|&i| expected[i] != actual[i]) {
+        Some(i) => Err(Error::VerifyFailed {
+            addr: addr + i as u32,
+            expected: expected[i],
+            actual: actual[i],
+        }),
+        None => Ok(()),
+    }
+}
@@ -1,16 +1,49 @@
+mod breakpoint;
+mod calibration;
 mod cfg;
+mod device;
+mod devicemap;
+mod diagnostic;
 mod driver;
 mod error;
+mod event;
+mod image;
 mod infomem;
+#[cfg(feature = "mock")]
+pub mod mock;
+#[cfg(feature = "tokio")]
+mod nonblocking;
+mod ops;
+mod power;
+mod protocol;
+mod regs;
+mod tlv;
+mod transcript;
+mod transport;
+mod usb;
 
-pub use cfg::{Cfg, TargetDriver};
-pub use driver::GdbCfg;
-pub(crate) use driver::MspDebug;
-pub use error::Error;
+pub use breakpoint::{symbol_address, Breakpoint, BreakpointKind, Stop};
+pub use cfg::{Capabilities, Cfg, Connection, DriverOptions, TargetDriver};
+pub use diagnostic::{Diagnostic, Severity};
+pub use driver::{GdbCfg, MspDebug, ProgramOptions, Running};
+pub use error::{BadInputReason, Error, FitReason, OutputTail};
+pub use event::Event;
+#[cfg(feature = "tokio")]
+pub use nonblocking::AsyncMspDebug;
+pub use power::{PowerProfile, PowerReading, PowerSample};
+pub use protocol::ShellType;
+pub use regs::{Register, Registers, StatusFlags};
+pub use tlv::{AdcCal, Calibration, DcoCal, DieRecord, RefCal, TempSensorCal, TlvEntry};
+pub use transport::{Pipe, Transport};
+pub use usb::{list_devices, UsbDevice};
 
 #[cfg(test)]
 mod tests {
-    use super::{Cfg, TargetDriver};
+    use super::{
+        Breakpoint, BreakpointKind, Calibration, Cfg, DcoCal, DieRecord, Error, ProgramOptions,
+        Register, Registers, StatusFlags, TargetDriver, UsbDevice,
+    };
+    use crate::device::{Core, DeviceInfo, Technology};
     use serial_test::serial;
 
     // Tests assume mspdebug is on the path.
@@ -37,6 +70,579 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_unsupported_option() {
+        // Checked before spawning, so the binary does not have to exist.
+        let mspdebug = Cfg::new()
+            .binary("does-not-exist")
+            .driver(TargetDriver::Sim)
+            .serial("1234")
+            .run();
+
+        match mspdebug {
+            Err(Error::UnsupportedOption { driver, option }) => {
+                assert_eq!(driver, TargetDriver::Sim);
+                assert_eq!(option, "-s");
+            }
+            Err(e) => panic!("expected UnsupportedOption, got {:?}", e),
+            Ok(_) => panic!("expected UnsupportedOption, got a session"),
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_unsupported_operation() {
+        // Rejected up front, before mspdebug (here, a stand-in that exits
+        // immediately) is asked to do anything.
+        let mut mspdebug = Cfg::new()
+            .binary("false")
+            .driver(TargetDriver::RomBsl)
+            .run()
+            .unwrap();
+
+        assert!(TargetDriver::RomBsl.capabilities().bsl_only);
+        match mspdebug.step(1) {
+            Err(Error::Unsupported { driver, .. }) => assert_eq!(driver, TargetDriver::RomBsl),
+            res => panic!("expected Unsupported, got {:?}", res),
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_child_exited() {
+        // Exits immediately without printing anything, like a crashed mspdebug.
+        let mut mspdebug = Cfg::new().binary("false").run().unwrap();
+
+        match mspdebug.wait_for_ready() {
+            Err(Error::ChildExited { status, .. }) => {
+                assert!(!status.unwrap().success())
+            }
+            res => panic!("expected ChildExited, got {:?}", res),
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(all(unix, feature = "tokio"))]
+    async fn test_async_child_exited() {
+        let mut mspdebug = Cfg::new().binary("false").run_async().await.unwrap();
+
+        match mspdebug.wait_for_ready().await {
+            Err(Error::ChildExited { status, .. }) => {
+                assert!(!status.unwrap().success())
+            }
+            res => panic!("expected ChildExited, got {:?}", res),
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_stderr() {
+        use std::sync::{Arc, Mutex};
+
+        let streamed = Arc::new(Mutex::new(Vec::new()));
+        let cb_streamed = streamed.clone();
+
+        // sh rejects mspdebug's arguments on stderr and exits.
+        let mut mspdebug = Cfg::new()
+            .binary("sh")
+            .on_stderr(move |l| cb_streamed.lock().unwrap().push(l.to_owned()))
+            .run()
+            .unwrap();
+
+        match mspdebug.wait_for_ready() {
+            Err(Error::ChildExited { last_output, .. }) => {
+                assert!(!last_output.stderr.is_empty());
+                assert_eq!(last_output.stderr, *streamed.lock().unwrap());
+            }
+            res => panic!("expected ChildExited, got {:?}", res),
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_reset() {
+        let mut mspdebug = Cfg::new().run().unwrap();
+
+        let cmd = mspdebug.reset();
+        assert!(
+            cmd.is_ok(),
+            "mspdebug could not reset: {:?}",
+            cmd.unwrap_err()
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_read_memory() {
+        let mut mspdebug = Cfg::new().run().unwrap();
+
+        let mem = mspdebug.read_memory(0x1ff8, 40);
+        assert!(
+            mem.is_ok(),
+            "mspdebug could not read memory: {:?}",
+            mem.as_ref().unwrap_err()
+        );
+        assert_eq!(mem.unwrap().len(), 40);
+    }
+
+    #[test]
+    #[serial]
+    fn test_write_memory() {
+        let mut mspdebug = Cfg::new().run().unwrap();
+
+        let data: Vec<u8> = (0..100).collect();
+        mspdebug.write_memory(0x200, &data).unwrap();
+        mspdebug.write_word(0x280, 0xbeef).unwrap();
+
+        assert_eq!(mspdebug.read_memory(0x200, 100).unwrap(), data);
+        assert_eq!(mspdebug.read_memory(0x280, 2).unwrap(), [0xef, 0xbe]);
+    }
+
+    #[test]
+    fn test_parse_hexdump_line() {
+        let (addr, data) = crate::protocol::parse_hexdump_line(
+            "    0c000: 31 40 00 04 3f 40 00 00 3f 90 00 00 05 24 1e 42 |1@..?@..?....$.B|",
+        )
+        .unwrap();
+        assert_eq!(addr, 0xc000);
+        assert_eq!(data[..4], [0x31, 0x40, 0x00, 0x04]);
+        assert_eq!(data.len(), 16);
+
+        let (addr, data) =
+            crate::protocol::parse_hexdump_line("    0200: ff ff               |..      |")
+                .unwrap();
+        assert_eq!(addr, 0x200);
+        assert_eq!(data, [0xff, 0xff]);
+    }
+
+    #[test]
+    #[serial]
+    fn test_registers() {
+        let mut mspdebug = Cfg::new().run().unwrap();
+
+        mspdebug.set_register(Register::R4, 0x1234).unwrap();
+        let regs = mspdebug.registers().unwrap();
+        assert_eq!(regs.get(Register::R4), 0x1234);
+    }
+
+    #[test]
+    #[serial]
+    fn test_run_halt() {
+        let mut mspdebug = Cfg::new().run().unwrap();
+
+        mspdebug.reset().unwrap();
+        let regs = mspdebug.step(1);
+        assert!(
+            regs.is_ok(),
+            "mspdebug could not step: {:?}",
+            regs.unwrap_err()
+        );
+
+        let running = mspdebug.run().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        let regs = running.halt();
+        assert!(
+            regs.is_ok(),
+            "mspdebug could not halt: {:?}",
+            regs.unwrap_err()
+        );
+
+        // Session is usable again after halting.
+        assert!(mspdebug.registers().is_ok());
+    }
+
+    #[test]
+    #[serial]
+    fn test_breakpoints() {
+        let mut mspdebug = Cfg::new().run().unwrap();
+
+        mspdebug.clear_breakpoints().unwrap();
+        let index = mspdebug.set_breakpoint(0xc000).unwrap();
+        let bps = mspdebug.breakpoints().unwrap();
+        assert_eq!(bps.len(), 1);
+        assert_eq!(bps[0].index, index);
+        assert_eq!(bps[0].addr, 0xc000);
+
+        mspdebug.delete_breakpoint(index).unwrap();
+        assert!(mspdebug.breakpoints().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_breakpoints() {
+        let output = [
+            "4 breakpoints available:",
+            "    0. 0x0c000 (main)",
+            "    2. 0x00200, write",
+        ]
+        .map(String::from);
+
+        let bps = Breakpoint::parse_table(&output).unwrap();
+        assert_eq!(bps.len(), 2);
+        assert_eq!(bps[0].addr, 0xc000);
+        assert_eq!(bps[0].symbol.as_deref(), Some("main"));
+        assert_eq!(bps[0].kind, BreakpointKind::Break);
+        assert_eq!(bps[1].index, 2);
+        assert_eq!(bps[1].symbol, None);
+        assert_eq!(bps[1].kind, BreakpointKind::Write);
+    }
+
+    #[test]
+    fn test_symbol_address() {
+        let main = crate::symbol_address(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/assets/blinky-g2553.elf"),
+            "main",
+        )
+        .unwrap();
+        assert!(main.is_some());
+    }
+
+    #[test]
+    fn test_parse_usb_list() {
+        let output = "\
+Devices on bus 001:
+    001:001 1d6b:0002
+    001:004 0451:f432 eZ430-RF2500 [serial: 2BFF46F5F7F44B16]
+Devices on bus 002:
+    002:007 2047:0013 eZ-FET
+";
+
+        let devs = UsbDevice::parse_list(output);
+        assert_eq!(devs.len(), 3);
+        assert_eq!(devs[0].driver, None);
+        assert_eq!((devs[1].bus, devs[1].device), (1, 4));
+        assert_eq!((devs[1].vid, devs[1].pid), (0x0451, 0xf432));
+        assert_eq!(devs[1].description, "eZ430-RF2500");
+        assert_eq!(devs[1].serial.as_deref(), Some("2BFF46F5F7F44B16"));
+        assert_eq!(devs[1].driver, Some(TargetDriver::Rf2500));
+        assert_eq!(devs[2].driver, Some(TargetDriver::EzFet));
+        assert_eq!(devs[2].serial, None);
+    }
+
+    #[test]
+    fn test_parse_segment_a() {
+        #[rustfmt::skip]
+        let mut seg_a = [
+            0x77, 0x8c, 0xfe, 0x16, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x10, 0x10, 0xe0, 0x7f, 0xfd, 0xff,
+            0x9c, 0x7f, 0xb4, 0x01, 0xe5, 0x01, 0xa3, 0x7f, 0x16, 0x01, 0x36, 0x01, 0xfe, 0x08, 0xff, 0xff,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, 0x08, 0x8e, 0x8f, 0x9e, 0x8e, 0x92, 0x8d, 0xb6, 0x86,
+        ];
+
+        let cal = Calibration::parse("MSP430G2xx3", 0x10c0, &seg_a).unwrap();
+        assert_eq!(cal.entries.len(), 4);
+        assert_eq!(
+            cal.dco[3],
+            DcoCal {
+                mhz: 1,
+                caldco: 0xb6,
+                calbc1: 0x86
+            }
+        );
+
+        let adc = cal.adc.unwrap();
+        assert_eq!(adc.offset, -3);
+        assert_eq!(
+            (adc.temp_sensor[0].t30, adc.temp_sensor[0].t85),
+            (0x1b4, 0x1e5)
+        );
+        assert_eq!(cal.reference[1].factor, 0x7fa3);
+
+        seg_a[63] = 0x87;
+        assert!(matches!(
+            Calibration::parse("MSP430G2xx3", 0x10c0, &seg_a),
+            Err(Error::BadChecksum { addr: 0x10c0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_crc16() {
+        // CRC-16/MCRF4XX (the same CRC with its output reflected) of the
+        // standard check string is 0x6f91.
+        assert_eq!(super::tlv::crc16(b"123456789"), 0x6f91u16.reverse_bits());
+    }
+
+    #[test]
+    fn test_parse_descriptor() {
+        // Header as given in the MSP430F5529 datasheet (SLAS590): info and
+        // CRC length 06h (2^6 32-bit words, the whole table), device ID 55h
+        // 29h at 01A04h. The CRC is per unit, so it is filled in below.
+        let mut tlv = vec![0x06, 0x06, 0x00, 0x00, 0x55, 0x29, 0x10, 0x10];
+        // Die record, ADC12 and REF calibration, end tag; the rest of the
+        // table reads as erased.
+        tlv.extend([
+            0x08, 0x0a, 0x78, 0x56, 0x34, 0x12, 0x10, 0x00, 0x20, 0x00, 0x00, 0x00,
+        ]);
+        tlv.extend([0x11, 0x10]);
+        tlv.extend(
+            [0x8000u16, 0x0005, 100, 200, 110, 210, 120, 220]
+                .map(u16::to_le_bytes)
+                .concat(),
+        );
+        tlv.extend([0x12, 0x06, 0x01, 0x80, 0x02, 0x80, 0x03, 0x80]);
+        tlv.resize(0x100, 0xff);
+
+        let crc = super::tlv::crc16(&tlv[4..]);
+        tlv[2..4].copy_from_slice(&crc.to_le_bytes());
+
+        let cal = Calibration::parse("MSP430F5529", 0x1a00, &tlv).unwrap();
+        assert_eq!(cal.device_id, Some(0x5529));
+        assert_eq!(cal.entries.len(), 3);
+        assert_eq!(
+            cal.die,
+            Some(DieRecord {
+                lot_wafer_id: 0x12345678,
+                die_x: 0x10,
+                die_y: 0x20,
+                test_results: 0
+            })
+        );
+
+        let adc = cal.adc.unwrap();
+        assert_eq!(adc.offset, 5);
+        assert_eq!(adc.temp_sensor[1].ref_mv, 2000);
+        assert_eq!(adc.temp_sensor[1].t85, 210);
+        assert_eq!(cal.reference[2].factor, 0x8003);
+
+        // The CRC covers the header's device ID and the end of the table.
+        let mut bad = tlv.clone();
+        bad[5] = 0x28;
+        assert!(matches!(
+            Calibration::parse("MSP430F5529", 0x1a00, &bad),
+            Err(Error::BadChecksum { .. })
+        ));
+        let mut bad = tlv.clone();
+        bad[0xff] = 0x00;
+        assert!(matches!(
+            Calibration::parse("MSP430F5529", 0x1a00, &bad),
+            Err(Error::BadChecksum { .. })
+        ));
+
+        // FR5969 (SLAS704): device ID 81h 69h.
+        tlv[4..6].copy_from_slice(&[0x81, 0x69]);
+        let crc = super::tlv::crc16(&tlv[4..]);
+        tlv[2..4].copy_from_slice(&crc.to_le_bytes());
+        let cal = Calibration::parse("MSP430FR5969", 0x1a00, &tlv).unwrap();
+        assert_eq!(cal.device_id, Some(0x8169));
+        assert_eq!(cal.adc.unwrap().temp_sensor[0].ref_mv, 1200);
+    }
+
+    #[test]
+    fn test_device_info() {
+        let g2 = DeviceInfo::lookup("MSP430G2xx3").unwrap();
+        assert_eq!(g2.core, Core::Cpu);
+        assert_eq!(g2.main_size(), 16 * 1024);
+        assert_eq!(g2.vector_table(), 0xffe0..0x10000);
+        assert_eq!(g2.infomem.unwrap().range(), 0x1000..0x1100);
+
+        let f5529 = DeviceInfo::lookup("MSP430F5529").unwrap();
+        assert_eq!(f5529.core, Core::CpuXv2);
+        assert_eq!(f5529.main_size(), 128 * 1024);
+        assert_eq!(f5529.ram, 0x1c00..0x4400);
+
+        let fr5969 = DeviceInfo::lookup("MSP430FR5969").unwrap();
+        assert_eq!(fr5969.technology, Technology::Fram);
+        assert_eq!(fr5969.bsl, Some(0x1000..0x1800));
+
+        assert!(DeviceInfo::lookup("Device_unknown").is_none());
+        assert!(DeviceInfo::lookup("MSP430X999").is_none());
+    }
+
+    // Play back scripted sessions instead of running mspdebug.
+    #[cfg(feature = "mock")]
+    mod replay {
+        use super::*;
+        use crate::mock::Transcript;
+        use crate::{Event, Severity, ShellType};
+        use std::io::{self, BufReader};
+        use std::net::TcpListener;
+        use std::time::Duration;
+
+        #[test]
+        fn test_replay() {
+            let recorded = "\
+# \"mspdebug\" \"--embedded\" \"sim\" \"-q\"
+[0.001020] > \\ready
+[0.001101] < :md 0x200 4
+[0.001232] > \\busy
+[0.001391] > :    00200: de ad be ef |....|
+[0.001402] > \\ready
+    ";
+            let transcript = Transcript::parse(recorded).unwrap();
+            let mut mspdebug = Cfg::new().replay(transcript).unwrap();
+
+            let mem = mspdebug.read_memory(0x200, 4).unwrap();
+            assert_eq!(mem, [0xde, 0xad, 0xbe, 0xef]);
+
+            let err = mspdebug.reset().unwrap_err();
+            assert!(matches!(err, Error::CommsError { .. }), "{:?}", err);
+        }
+
+        #[test]
+        fn test_subscribe() {
+            let transcript = Transcript::new()
+                .ready()
+                .expect(":reset")
+                .busy()
+                .output("-fet: reset")
+                .output("!warning: device does not support power profiling")
+                .output(":done")
+                .ready();
+            let mut mspdebug = Cfg::new().replay(transcript).unwrap();
+            let events = mspdebug.subscribe();
+
+            mspdebug.reset().unwrap();
+            assert_eq!(
+                events.try_iter().collect::<Vec<_>>(),
+                [
+                    Event::Shell(ShellType::Ready),
+                    Event::Shell(ShellType::Busy),
+                    Event::Debug("fet: reset".into()),
+                    Event::Warning("warning: device does not support power profiling".into()),
+                    Event::Output("done".into()),
+                    Event::Shell(ShellType::Ready),
+                ]
+            );
+        }
+
+        #[test]
+        fn test_profile_power() {
+            let transcript = Transcript::new()
+                .ready()
+                .expect(":run")
+                .busy()
+                .output("\\power-sample-us 1000")
+                .output("\\power-samples AMAAADgBAAAEwAAAKgEAAA==")
+                .expect("\\break")
+                .output(":    ( PC: 0c004)  ( R4: 00000)  ( R8: 00000)  (R12: 00000)")
+                .output(":    ( SP: 003fe)  ( R5: 00000)  ( R9: 00000)  (R13: 00000)")
+                .output(":    ( SR: 00000)  ( R6: 00000)  (R10: 00000)  (R14: 00000)")
+                .output(":    ( R3: 00000)  ( R7: 00000)  (R11: 00000)  (R15: 00000)")
+                .ready()
+                .command("break", &["2 breakpoints available:"]);
+            let mut mspdebug = Cfg::new()
+                .driver(TargetDriver::EzFet)
+                .replay(transcript)
+                .unwrap();
+
+            let profile = mspdebug.profile_power(Duration::from_millis(10)).unwrap();
+            assert_eq!(profile.sample_period(), Duration::from_micros(1000));
+            assert_eq!(profile.samples()[1].mab, 0xc004);
+            assert_eq!(profile.samples()[1].current_ua, 298);
+
+            let mut csv = Vec::new();
+            profile.write_csv(&mut csv).unwrap();
+            assert_eq!(
+                String::from_utf8(csv).unwrap(),
+                "time_us,mab,current_ua,energy_uj\n\
+                 0,0xc000,312,0.936000\n\
+                 1000,0xc004,298,1.830000\n"
+            );
+
+            let mut sim = Cfg::new().replay(Transcript::new()).unwrap();
+            assert!(matches!(
+                sim.profile_power(Duration::from_millis(10)),
+                Err(Error::Unsupported { .. })
+            ));
+        }
+
+        #[test]
+        fn test_run_until() {
+            let transcript = Transcript::new()
+                .ready()
+                .command("setbreak 0xc004", &["Set breakpoint 0"])
+                .expect(":run")
+                .busy()
+                .output(":    ( PC: 0c004)  ( R4: 00000)  ( R8: 00000)  (R12: 00000)")
+                .output(":    ( SP: 003fe)  ( R5: 00000)  ( R9: 00000)  (R13: 00000)")
+                .output(":    ( SR: 00000)  ( R6: 00000)  (R10: 00000)  (R14: 00000)")
+                .output(":    ( R3: 00000)  ( R7: 00000)  (R11: 00000)  (R15: 00000)")
+                .ready()
+                .command("break", &["2 breakpoints available:", "    0. 0x0c004"])
+                .expect(":delbreak 0")
+                .busy()
+                .output("!delbreak: no such breakpoint")
+                .ready();
+            let mut mspdebug = Cfg::new().replay(transcript).unwrap();
+
+            // The stop is returned even though the breakpoint wasn't removed.
+            let stop = mspdebug.run_until(0xc004).unwrap();
+            assert_eq!(stop.breakpoint.map(|b| b.index), Some(0));
+
+            let diags = mspdebug.drain_diagnostics();
+            assert_eq!(diags.len(), 1);
+            assert_eq!(diags[0].severity, Severity::Warning);
+            assert!(diags[0].message.contains("breakpoint 0"));
+        }
+
+        #[test]
+        fn test_pattern() {
+            let recorded = Transcript::parse("< :sym find *\n").unwrap();
+            assert!(recorded.play(&b":sym find *\n"[..], io::sink()).is_ok());
+            assert!(recorded.play(&b":sym find main\n"[..], io::sink()).is_err());
+
+            let pattern = Transcript::new().expect_matching(":prog *.elf");
+            assert!(pattern
+                .play(&b":prog /tmp/blinky.elf\n"[..], io::sink())
+                .is_ok());
+            assert!(pattern
+                .play(&b":prog blinky.hex\n"[..], io::sink())
+                .is_err());
+
+            let text = pattern.to_string();
+            assert_eq!(Transcript::parse(&text).unwrap(), pattern);
+        }
+
+        #[test]
+        fn test_record() {
+            let path = std::env::temp_dir().join(format!("mspdebug-record-{}", std::process::id()));
+            let transcript = Transcript::new().ready().command("reset", &[]);
+            let mut mspdebug = Cfg::new().record(&path).replay(transcript.clone()).unwrap();
+
+            mspdebug.reset().unwrap();
+            drop(mspdebug);
+
+            let recorded = Transcript::load(&path).unwrap();
+            assert!(recorded.steps().eq(transcript.steps()));
+        }
+
+        #[test]
+        fn test_connect() {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let addr = listener.local_addr().unwrap();
+            let transcript = Transcript::new().ready().command("reset", &[]);
+
+            let remote = std::thread::spawn(move || {
+                let (stream, _) = listener.accept().unwrap();
+                transcript.play(BufReader::new(stream.try_clone().unwrap()), stream)
+            });
+
+            let mut mspdebug = Cfg::new().connect(addr).unwrap();
+            mspdebug.reset().unwrap();
+            drop(mspdebug);
+
+            remote.join().unwrap().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_parse_registers() {
+        let output = [
+            "    ( PC: 0c0b4)  ( R4: 0ff7a)  ( R8: 0dfff)  (R12: 00000)",
+            "    ( SP: 003fe)  ( R5: 05a08)  ( R9: 0ffbf)  (R13: 0ffff)",
+            "    ( SR: 00009)  ( R6: 0ffff)  (R10: 0fffd)  (R14: 0ffff)",
+            "    ( R3: 00000)  ( R7: 0ffff)  (R11: 0ffff)  (R15: 1ff7f)",
+            "main:",
+            "    0c0b4: 31 40 00 04      MOV     #0x0400, SP",
+        ]
+        .map(String::from);
+
+        let regs = Registers::parse(&output).unwrap();
+        assert_eq!(regs.pc(), 0xc0b4);
+        assert_eq!(regs.sp(), 0x3fe);
+        assert_eq!(regs.sr(), StatusFlags::C | StatusFlags::GIE);
+        assert_eq!(regs.get(Register::R15), 0x1ff7f);
+    }
+
     // Requires a dev board w/ rf2500- MSP-EXP430G2 is an example.
     mod rf2500 {
         use super::*;
@@ -80,5 +686,21 @@ mod tests {
                 cmd.unwrap_err()
             );
         }
+
+        #[test]
+        #[serial]
+        fn test_prog_verify() {
+            let mut mspdebug = Cfg::new().driver(TargetDriver::Rf2500).run().unwrap();
+
+            let cmd = mspdebug.program_with(
+                concat!(env!("CARGO_MANIFEST_DIR"), "/assets/blinky-g2553.elf"),
+                ProgramOptions::default().verify(),
+            );
+            assert!(
+                cmd.is_ok(),
+                "mspdebug could not verify ELF file: {:?}",
+                cmd.unwrap_err()
+            );
+        }
     }
 }
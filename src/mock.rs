@@ -0,0 +1,347 @@
+/*! Scriptable stand-in for `mspdebug`, for testing without hardware.
+
+The `mspdebug-mock` binary (built with the `mock` feature) replays a
+[`Transcript`] read from the file named by [`TRANSCRIPT_VAR`], instead of
+talking to a programmer. Point a [`Cfg`] at it to test code built on this
+crate deterministically:
+
+```no_run
+use mspdebug_embedded::mock::{Transcript, TRANSCRIPT_VAR};
+use mspdebug_embedded::Cfg;
+
+let transcript = Transcript::new()
+    .ready()
+    .command("reset", &[]);
+transcript.save("reset.txt").unwrap();
+
+let mut msp = Cfg::new()
+    .binary("target/debug/mspdebug-mock")
+    .env(TRANSCRIPT_VAR, "reset.txt")
+    .run()
+    .unwrap();
+msp.reset().unwrap();
+```
+
+The command-line arguments `mspdebug` would get are ignored, so any
+[`TargetDriver`](crate::TargetDriver) can be "opened".
+*/
+
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, BufReader, PipeReader, PipeWriter, Write};
+use std::path::Path;
+use std::process::ExitStatus;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+#[cfg(doc)]
+use super::Cfg;
+use crate::transcript::Entry;
+pub use crate::transcript::Step;
+use crate::transport::{Pipe, Transport};
+
+/// Environment variable `mspdebug-mock` reads the transcript path from.
+pub const TRANSCRIPT_VAR: &str = "MSPDEBUG_MOCK_TRANSCRIPT";
+
+/** An `mspdebug` embedded-mode session, either scripted by hand or recorded
+with [`Cfg::record`](crate::Cfg::record).
+
+Transcripts are stored as text, one step per line. `>` lines are printed by
+`mspdebug`, `<` lines are read by it, `~` lines are read by it and may
+contain `*` wildcards, and `#` lines are comments. Recorded sessions only
+contain `<` lines, so input is replayed exactly as it was recorded. Recorded
+sessions prefix each step with the number of seconds since the session
+started:
+
+```text
+# "mspdebug" "--embedded" "sim" "-q"
+[0.001020] > :Device: Simulation
+[0.001034] > \ready
+[0.001101] < :md 0x200 16
+[0.001232] > \busy
+[0.001391] > :    00200: ff ff ff ff ff ff ff ff ff ff ff ff ff ff ff ff |................|
+[0.001402] > \ready
+```
+
+Timestamps are informational; [`Transcript::play`] and
+[`Cfg::replay`](crate::Cfg::replay) go as fast as the host does.
+*/
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Transcript {
+    entries: Vec<Entry>,
+}
+
+impl Transcript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn steps(&self) -> impl Iterator<Item = &Step> + '_ {
+        self.entries.iter().map(|e| &e.step)
+    }
+
+    /// Parse a transcript in the text format described above.
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let mut entries = Vec::new();
+
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            let bad_line = || {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("transcript line {}: {:?}", n + 1, line),
+                )
+            };
+
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (time, rest) = match line.strip_prefix('[') {
+                Some(stamped) => {
+                    let (secs, rest) = stamped.split_once("] ").ok_or_else(bad_line)?;
+                    let secs = secs.trim().parse().map_err(|_| bad_line())?;
+                    let time = Duration::try_from_secs_f64(secs).map_err(|_| bad_line())?;
+
+                    (Some(time), rest)
+                }
+                None => (None, line),
+            };
+
+            let step = if let Some(out) = rest.strip_prefix("> ") {
+                Step::Output(out.to_owned())
+            } else if let Some(inp) = rest.strip_prefix("< ") {
+                Step::Input(inp.to_owned())
+            } else if let Some(pat) = rest.strip_prefix("~ ") {
+                Step::Pattern(pat.to_owned())
+            } else {
+                return Err(bad_line());
+            };
+
+            entries.push(Entry { step, time });
+        }
+
+        Ok(Transcript { entries })
+    }
+
+    pub fn load<P>(path: P) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn save<P>(&self, path: P) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        fs::write(path, self.to_string())
+    }
+
+    fn push(mut self, step: Step) -> Self {
+        self.entries.push(Entry { step, time: None });
+        self
+    }
+
+    /// Print `line`, sigil included.
+    pub fn output<S>(self, line: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.push(Step::Output(line.into()))
+    }
+
+    /// Expect to read `line` (e.g. `:reset` or `\break`).
+    pub fn expect<S>(self, line: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.push(Step::Input(line.into()))
+    }
+
+    /// Expect to read a line matching `pattern`, where `*` matches any run of
+    /// characters (e.g. `:prog *.elf`).
+    pub fn expect_matching<S>(self, pattern: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.push(Step::Pattern(pattern.into()))
+    }
+
+    pub fn ready(self) -> Self {
+        self.output("\\ready")
+    }
+
+    pub fn busy(self) -> Self {
+        self.output("\\busy")
+    }
+
+    /// Expect `cmd`, then print `output` as normal (`:`) lines and finish with
+    /// `\ready`; the usual shape of an `mspdebug` command.
+    pub fn command(self, cmd: &str, output: &[&str]) -> Self {
+        self.expect(format!(":{}", cmd)).respond(output)
+    }
+
+    /// [`Transcript::command`], for a command matching `pattern` (see
+    /// [`Transcript::expect_matching`]).
+    pub fn command_matching(self, pattern: &str, output: &[&str]) -> Self {
+        self.expect_matching(format!(":{}", pattern))
+            .respond(output)
+    }
+
+    fn respond(self, output: &[&str]) -> Self {
+        output
+            .iter()
+            .fold(self.busy(), |t, l| t.output(format!(":{}", l)))
+            .ready()
+    }
+
+    /** Play the transcript against `input` and `output`, as `mspdebug` would.
+
+    Once the script is done, input is read until EOF, like `mspdebug` does
+    before exiting. Input that doesn't match the script is reported on
+    `output` as an error (`!`) line and returned as an
+    [`io::ErrorKind::InvalidData`] error.
+    */
+    pub fn play<R, W>(&self, mut input: R, mut output: W) -> io::Result<()>
+    where
+        R: BufRead,
+        W: Write,
+    {
+        for step in self.steps() {
+            match step {
+                Step::Output(out) => {
+                    writeln!(output, "{}", out)?;
+                    output.flush()?;
+                }
+                Step::Input(exp) | Step::Pattern(exp) => match Self::read_line(&mut input)? {
+                    Some(got) if Self::matches(step, &got) => {}
+                    Some(got) => {
+                        return Self::mismatch(
+                            &mut output,
+                            format!("expected {:?}, got {:?}", exp, got),
+                        )
+                    }
+                    None => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            format!("expected {:?}, got EOF", exp),
+                        ))
+                    }
+                },
+            }
+        }
+
+        match Self::read_line(&mut input)? {
+            Some(got) => Self::mismatch(&mut output, format!("expected EOF, got {:?}", got)),
+            None => Ok(()),
+        }
+    }
+
+    fn read_line<R>(input: &mut R) -> io::Result<Option<String>>
+    where
+        R: BufRead,
+    {
+        let mut line = String::new();
+
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(line.trim_end_matches(['\r', '\n']).to_owned()))
+    }
+
+    fn mismatch<W>(output: &mut W, msg: String) -> io::Result<()>
+    where
+        W: Write,
+    {
+        writeln!(output, "!transcript: {}", msg)?;
+        output.flush()?;
+
+        Err(io::Error::new(io::ErrorKind::InvalidData, msg))
+    }
+
+    /// Match `got` against an input step.
+    fn matches(step: &Step, got: &str) -> bool {
+        let pattern = match step {
+            Step::Pattern(p) => p,
+            Step::Input(exp) => return exp == got,
+            Step::Output(_) => return false,
+        };
+
+        let mut parts = pattern.split('*');
+        let first = parts.next().unwrap_or("");
+        let Some(mut rest) = got.strip_prefix(first) else {
+            return false;
+        };
+
+        let mut parts = parts.peekable();
+        while let Some(part) = parts.next() {
+            if parts.peek().is_none() {
+                return rest.ends_with(part);
+            }
+
+            match rest.find(part) {
+                Some(i) => rest = &rest[i + part.len()..],
+                None => return false,
+            }
+        }
+
+        rest.is_empty()
+    }
+}
+
+impl fmt::Display for Transcript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{}", entry)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A [`Transcript`] played on a background thread, standing in for `mspdebug`.
+/// The thread ends by itself once its input is closed.
+pub(crate) struct Replay {
+    pipe: Pipe<PipeReader, PipeWriter>,
+    player: Option<JoinHandle<io::Result<()>>>,
+}
+
+impl Replay {
+    pub(crate) fn new(transcript: Transcript) -> io::Result<Self> {
+        let (stdout, player_out) = io::pipe()?;
+        let (player_in, stdin) = io::pipe()?;
+
+        let player = thread::spawn(move || transcript.play(BufReader::new(player_in), player_out));
+
+        Ok(Self {
+            pipe: Pipe::new(stdout, stdin),
+            player: Some(player),
+        })
+    }
+}
+
+impl io::Write for Replay {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pipe.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.pipe.flush()
+    }
+}
+
+impl Transport for Replay {
+    fn take_reader(&mut self) -> io::Result<Box<dyn io::Read + Send>> {
+        self.pipe.take_reader()
+    }
+
+    fn wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        match self.player.take().map(JoinHandle::join) {
+            Some(Ok(res)) => res.map(|_| None),
+            Some(Err(_)) => Err(io::Error::other("replay thread panicked")),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,522 @@
+use std::mem;
+use std::path::Path;
+use std::process::{ExitStatus, Stdio};
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+use command_group::{AsyncCommandGroup, AsyncGroupChild};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{ChildStdin, ChildStdout, Command};
+use tokio::task::JoinHandle;
+use tokio::time::{self, Instant};
+
+use crate::breakpoint::{Breakpoint, BreakpointKind, Stop};
+use crate::diagnostic::Diagnostic;
+use crate::driver::ProgramFlags;
+use crate::error::OutputTail;
+use crate::ops::{self, Op, Reply, Step};
+use crate::power::PowerProfile;
+use crate::protocol::{Protocol, Tail, WaitMode};
+use crate::regs::{Register, Registers};
+use crate::tlv::Calibration;
+
+#[cfg(doc)]
+use super::MspDebug;
+use super::{Cfg, Error, Event, GdbCfg, ProgramOptions};
+
+/// Kills the process group if the command it guards doesn't finish, e.g.
+/// because its future was dropped.
+struct KillGuard<'a>(Option<&'a mut AsyncGroupChild>);
+
+impl KillGuard<'_> {
+    fn disarm(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for KillGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(c) = self.0.take() {
+            let _ = c.kill();
+        }
+    }
+}
+
+/// Run a [`Session`] method, killing `mspdebug` if the future is cancelled
+/// part-way through.
+macro_rules! guarded {
+    ($self:ident . $method:ident ( $($arg:expr),* )) => {{
+        let guard = KillGuard(Some(&mut $self.child));
+        let res = $self.session.$method($($arg),*).await;
+        guard.disarm();
+
+        $self.check(res).await
+    }};
+}
+
+/** An `mspdebug` session for use from a `tokio` runtime (feature `tokio`).
+
+Created by [`Cfg::run_async`]. Offers the same commands as [`MspDebug`], as
+`async` methods; see there for details. `mspdebug` always runs in its own
+process group, but as with [`MspDebug`], [`Cfg::group`] must be set for
+[`AsyncMspDebug::gdb`] and not for programming. If a future returned by one of the methods is dropped before it
+completes, e.g. by [`tokio::time::timeout`] or `select!`, the whole group is
+killed, since there is no telling what state `mspdebug` was left in. The
+session cannot be used afterwards.
+
+Instead of a [`Running`](crate::Running) handle, [`AsyncMspDebug::run`]
+returns once the target runs, and [`AsyncMspDebug::halt`] or
+[`AsyncMspDebug::wait`] stop it. Dropping the session while the target runs
+kills `mspdebug`.
+*/
+pub struct AsyncMspDebug {
+    child: AsyncGroupChild,
+    session: Session,
+}
+
+struct Session {
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    partial: Vec<u8>,
+    stderr_reader: Option<JoinHandle<()>>,
+    proto: Protocol,
+}
+
+impl Cfg {
+    /// Spawn `mspdebug` for an [`AsyncMspDebug`] session. Must be called from
+    /// within a `tokio` runtime.
+    pub async fn run_async(mut self) -> Result<AsyncMspDebug, Error> {
+        let std_cmd = self.command()?;
+        let recorder = self.recorder(&format!("{:?}", std_cmd))?;
+
+        let mut cmd = Command::from(std_cmd);
+        cmd.stderr(if self.stderr_lines > 0 || self.on_stderr.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped());
+
+        let mut child = cmd.group_spawn().map_err(Error::SpawnError)?;
+
+        let inner = child.inner();
+        let stdin = inner.stdin.take().ok_or(Error::StreamError("stdin"))?;
+        let stdout = inner.stdout.take().ok_or(Error::StreamError("stdout"))?;
+
+        let stderr_tail = Tail::new(self.stderr_lines);
+        let stderr_reader = inner.stderr.take().map(|e| {
+            let tail = stderr_tail.clone();
+            let mut cb = self.on_stderr.take();
+
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(e).lines();
+                while let Ok(Some(l)) = lines.next_line().await {
+                    if let Some(cb) = cb.as_mut() {
+                        cb(&l);
+                    }
+                    tail.push(&l);
+                }
+            })
+        });
+
+        Ok(AsyncMspDebug {
+            child,
+            session: Session {
+                stdin,
+                stdout: BufReader::new(stdout),
+                partial: Vec::new(),
+                stderr_reader,
+                proto: Protocol::new(&self, stderr_tail, recorder),
+            },
+        })
+    }
+}
+
+impl AsyncMspDebug {
+    /// Kill `mspdebug` after a timeout, and fill in how it exited if it did.
+    async fn check<T>(&mut self, res: Result<T, Error>) -> Result<T, Error> {
+        match res {
+            Err(Error::Timeout(t)) => {
+                // A hung programmer won't recover by itself; don't leave
+                // mspdebug (or its process group) behind.
+                let _ = self.child.kill();
+                Err(Error::Timeout(t))
+            }
+            Err(Error::ChildExited {
+                status: None,
+                last_output,
+            }) => {
+                let status = self.child.wait().await.ok();
+
+                // stderr is closed once mspdebug is gone, so this won't block
+                // for long.
+                if let Some(r) = self.session.stderr_reader.take() {
+                    let _ = r.await;
+                }
+
+                Err(Error::ChildExited {
+                    status,
+                    last_output: OutputTail {
+                        stderr: self.session.proto.stderr_tail.lines(),
+                        ..last_output
+                    },
+                })
+            }
+            res => res,
+        }
+    }
+
+    /// Wait until `mspdebug` reports that it is ready to accept a command.
+    pub async fn wait_for_ready(&mut self) -> Result<(), Error> {
+        guarded!(self.wait_for_ready_or_busy(WaitMode::Ready, None))
+    }
+
+    /// Wait until `mspdebug` reports that it started executing a command.
+    pub async fn wait_for_busy(&mut self) -> Result<(), Error> {
+        guarded!(self.wait_for_ready_or_busy(WaitMode::Busy, None))
+    }
+
+    /// Recent lines `mspdebug` printed on stderr, oldest first.
+    pub fn stderr(&self) -> Vec<String> {
+        self.session.proto.stderr_tail.lines()
+    }
+
+    /// Timeout applied to every wait for `mspdebug` to become ready or busy.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.session.proto.timeout
+    }
+
+    /// See [`MspDebug::set_timeout`].
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.session.proto.timeout = timeout;
+    }
+
+    /// Warnings and ignored errors collected since the last call to
+    /// [`AsyncMspDebug::drain_diagnostics`].
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.session.proto.diagnostics
+    }
+
+    /// Remove and return all collected warnings and ignored errors.
+    pub fn drain_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.session.proto.diagnostics)
+    }
+
+    /// Call `cb` for every warning or ignored error as soon as it is read.
+    pub fn on_diagnostic<F>(&mut self, cb: F)
+    where
+        F: FnMut(&Diagnostic) + Send + 'static,
+    {
+        self.session.proto.on_diagnostic = Some(Box::new(cb));
+    }
+
+    /// See [`MspDebug::subscribe`]. Events are sent as lines are read; the
+    /// receiver never blocks the session.
+    pub fn subscribe(&mut self) -> Receiver<Event> {
+        self.session.proto.subscribers.subscribe()
+    }
+
+    /// Name of the attached device, as reported by `mspdebug` on startup.
+    pub fn device(&self) -> Option<&str> {
+        self.session.proto.device.as_deref()
+    }
+
+    /// Run an arbitrary `mspdebug` command and return its normal output; see
+    /// [`MspDebug::command`].
+    pub async fn command(&mut self, cmd: &str) -> Result<Vec<String>, Error> {
+        guarded!(self.perform(ops::command(cmd.to_owned())))
+    }
+
+    /// Reset the target and halt the CPU (`reset`).
+    pub async fn reset(&mut self) -> Result<(), Error> {
+        guarded!(self.perform(ops::reset()))
+    }
+
+    /// Erase main memory of the target (`erase`).
+    pub async fn erase(&mut self) -> Result<(), Error> {
+        guarded!(self.perform(ops::erase()))
+    }
+
+    /// Erase `length` bytes starting at `origin`, one `sector_size`-sized
+    /// segment at a time (`erase segrange`).
+    pub async fn erase_segrange(
+        &mut self,
+        origin: u16,
+        length: u16,
+        sector_size: u16,
+    ) -> Result<(), Error> {
+        guarded!(self.perform(ops::erase_segrange(origin, length, sector_size)))
+    }
+
+    /// Read Information Memory segment A; see [`MspDebug::segment_a`].
+    pub async fn segment_a(&mut self) -> Result<Vec<u8>, Error> {
+        guarded!(self.perform(ops::segment_a()))
+    }
+
+    /// Read and decode the factory calibration; see
+    /// [`MspDebug::calibration`].
+    pub async fn calibration(&mut self) -> Result<Calibration, Error> {
+        guarded!(self.perform(ops::calibration()))
+    }
+
+    /// Erase Information Memory segment A and write `data` to it.
+    pub async fn write_segment_a(&mut self, data: &[u8]) -> Result<(), Error> {
+        guarded!(self.perform(ops::write_segment_a(data.to_vec())))
+    }
+
+    /// Write back the segment A backup; see [`MspDebug::restore_segment_a`].
+    pub async fn restore_segment_a(&mut self) -> Result<(), Error> {
+        guarded!(self.perform(ops::restore_segment_a()))
+    }
+
+    /// Read `len` bytes of target memory starting at `addr` (`md`).
+    pub async fn read_memory(&mut self, addr: u32, len: usize) -> Result<Vec<u8>, Error> {
+        guarded!(self.perform(ops::read_memory(addr, len)))
+    }
+
+    /// Write `data` to target memory starting at `addr` (`mw`).
+    pub async fn write_memory(&mut self, addr: u32, data: &[u8]) -> Result<(), Error> {
+        guarded!(self.perform(ops::write_memory(addr, data.to_vec())))
+    }
+
+    /// Write a single 16-bit little-endian word to target memory at `addr`.
+    pub async fn write_word(&mut self, addr: u32, word: u16) -> Result<(), Error> {
+        self.write_memory(addr, &word.to_le_bytes()).await
+    }
+
+    /// Let the target run freely (`run`). Returns as soon as `mspdebug`
+    /// acknowledges the command.
+    pub async fn run(&mut self) -> Result<(), Error> {
+        guarded!(self.perform(ops::run()))
+    }
+
+    /// Halt the target and report where it stopped; see [`MspDebug::halt`].
+    pub async fn halt(&mut self) -> Result<Stop, Error> {
+        guarded!(self.perform(ops::halt()))
+    }
+
+    /// Wait for the target to stop by itself, e.g. at a breakpoint.
+    pub async fn wait(&mut self) -> Result<Stop, Error> {
+        guarded!(self.perform(ops::finish_run(false)))
+    }
+
+    /// Run the target for `duration` while sampling its current; see
+    /// [`MspDebug::profile_power`].
+    pub async fn profile_power(&mut self, duration: Duration) -> Result<PowerProfile, Error> {
+        guarded!(self.perform(ops::profile_power(duration)))
+    }
+
+    /// Run until the CPU reaches `addr`, using a temporary breakpoint; see
+    /// [`MspDebug::run_until`].
+    pub async fn run_until(&mut self, addr: u32) -> Result<Stop, Error> {
+        guarded!(self.perform(ops::run_until(addr)))
+    }
+
+    /// Single-step the CPU `count` instructions and return the registers
+    /// afterwards (`step`).
+    pub async fn step(&mut self, count: u32) -> Result<Registers, Error> {
+        guarded!(self.perform(ops::step(count)))
+    }
+
+    /// Set a breakpoint at `addr` and return its index (`setbreak`).
+    pub async fn set_breakpoint(&mut self, addr: u32) -> Result<usize, Error> {
+        self.set_watchpoint(addr, BreakpointKind::Break).await
+    }
+
+    /// Set a breakpoint or watchpoint of the given kind at `addr` and return
+    /// its index.
+    pub async fn set_watchpoint(
+        &mut self,
+        addr: u32,
+        kind: BreakpointKind,
+    ) -> Result<usize, Error> {
+        guarded!(self.perform(ops::set_watchpoint(addr, kind)))
+    }
+
+    /// Delete the breakpoint with the given index (`delbreak`).
+    pub async fn delete_breakpoint(&mut self, index: usize) -> Result<(), Error> {
+        guarded!(self.perform(ops::delete_breakpoint(index)))
+    }
+
+    /// Delete all breakpoints (`delbreak`).
+    pub async fn clear_breakpoints(&mut self) -> Result<(), Error> {
+        guarded!(self.perform(ops::clear_breakpoints()))
+    }
+
+    /// List breakpoints that are currently set (`break`).
+    pub async fn breakpoints(&mut self) -> Result<Vec<Breakpoint>, Error> {
+        guarded!(self.perform(ops::breakpoints()))
+    }
+
+    /// Read all CPU registers (`regs`).
+    pub async fn registers(&mut self) -> Result<Registers, Error> {
+        guarded!(self.perform(ops::registers()))
+    }
+
+    /// Set a single CPU register (`set`).
+    pub async fn set_register(&mut self, reg: Register, value: u32) -> Result<(), Error> {
+        guarded!(self.perform(ops::set_register(reg, value)))
+    }
+
+    /// Program the target with an ELF file (`prog`); see
+    /// [`MspDebug::program`].
+    pub async fn program<F>(&mut self, filename: F) -> Result<(), Error>
+    where
+        F: AsRef<Path>,
+    {
+        self.program_with(filename, ProgramOptions::default()).await
+    }
+
+    /// Program the target with an ELF file, using the given options.
+    pub async fn program_with<F>(&mut self, filename: F, opts: ProgramOptions) -> Result<(), Error>
+    where
+        F: AsRef<Path>,
+    {
+        guarded!(self.perform(ops::program(
+            filename.as_ref().to_owned(),
+            opts.flags.contains(ProgramFlags::ALLOW_MISFIT),
+            opts.flags.contains(ProgramFlags::VERIFY),
+        )))
+    }
+
+    /** Run `mspdebug` in `gdb` server mode and spawn a `msp430-elf-gdb`
+    session; see [`MspDebug::gdb`].
+    */
+    pub async fn gdb<F>(mut self, filename: F, cfg: GdbCfg) -> Result<ExitStatus, Error>
+    where
+        F: AsRef<Path>,
+    {
+        let args = guarded!(self.perform(ops::start_gdb(filename.as_ref().to_owned(), cfg)))?;
+
+        let mut gdb = match Command::new("msp430-elf-gdb").args(&args).spawn() {
+            Ok(gdb) => gdb,
+            Err(e) => {
+                // Nothing will connect to the gdb server, so mspdebug won't
+                // exit by itself.
+                let _ = self.child.kill();
+                return Err(Error::SpawnError(e));
+            }
+        };
+
+        // When gdb exits, mspdebug will too.
+        gdb.wait().await.map_err(Error::GdbError)
+    }
+}
+
+impl Drop for AsyncMspDebug {
+    fn drop(&mut self) {
+        if self.session.proto.running {
+            let _ = self.child.kill();
+        }
+    }
+}
+
+impl Session {
+    /// Read a line. Cancel safe: a line read only in part is finished by the
+    /// next call.
+    async fn get_line(&mut self, deadline: Option<Instant>) -> Result<String, Error> {
+        let read = self.stdout.read_until(b'\n', &mut self.partial);
+        let n = match deadline {
+            Some(d) => time::timeout_at(d, read)
+                .await
+                .map_err(|_| Error::Timeout(self.proto.timeout.unwrap_or_default()))?,
+            None => read.await,
+        }
+        .map_err(Error::ReadError)?;
+
+        if n == 0 {
+            // Status and stderr are filled in by AsyncMspDebug::check.
+            return Err(self.proto.child_exited(None));
+        }
+
+        Ok(String::from_utf8_lossy(&mem::take(&mut self.partial)).into_owned())
+    }
+
+    async fn wait_for_ready_or_busy(
+        &mut self,
+        mode: WaitMode,
+        mut output: Option<&mut Vec<String>>,
+    ) -> Result<(), Error> {
+        if self.proto.waited(mode) {
+            return Ok(());
+        }
+
+        let deadline = self.proto.timeout.map(|t| Instant::now() + t);
+
+        loop {
+            let line = self.get_line(deadline).await?;
+            if self.proto.feed(&line, mode, output.as_deref_mut())? {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn write_line(&mut self, line: &str) -> Result<(), Error> {
+        let line = format!("{}\n", line);
+
+        self.stdin
+            .write_all(line.as_bytes())
+            .await
+            .map_err(Error::WriteError)?;
+        self.proto.sent(line.as_bytes());
+
+        Ok(())
+    }
+
+    async fn start_command(&mut self, cmd: &str) -> Result<(), Error> {
+        self.wait_for_ready_or_busy(WaitMode::Ready, None).await?;
+        self.write_line(&format!(":{}", cmd)).await?;
+        self.wait_for_ready_or_busy(WaitMode::Busy, None).await
+    }
+
+    /// Do what `step` asks of `mspdebug`, and return the output collected.
+    async fn exchange(&mut self, step: Step) -> Reply {
+        let mut output = Vec::new();
+
+        match step {
+            Step::Ready => self.wait_for_ready_or_busy(WaitMode::Ready, None).await?,
+            Step::Start(cmd) => self.start_command(&cmd).await?,
+            Step::Command(cmd) => {
+                self.start_command(&cmd).await?;
+                self.wait_for_ready_or_busy(WaitMode::Ready, Some(&mut output))
+                    .await?;
+            }
+            Step::Pause(duration) => {
+                // Keep reading while the target runs, so that mspdebug
+                // doesn't block on a full pipe.
+                let end = Instant::now() + duration;
+                while let Ok(line) = time::timeout_at(end, self.get_line(None)).await {
+                    if self.proto.feed(&line?, WaitMode::Ready, None)? {
+                        // The target stopped by itself, e.g. at a breakpoint.
+                        self.proto.running = false;
+                        break;
+                    }
+                }
+            }
+            Step::Stop(interrupt) => {
+                if interrupt {
+                    self.write_line("\\break").await?;
+                }
+                self.wait_for_ready_or_busy(WaitMode::Ready, Some(&mut output))
+                    .await?;
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Run `op` to completion.
+    async fn perform<T>(&mut self, mut op: Op<T>) -> Result<T, Error> {
+        loop {
+            op = match op {
+                Op::Done(val) => return Ok(val),
+                Op::Next(next) => next(&mut self.proto)?,
+                Op::Step(step, then) => {
+                    let reply = self.exchange(step).await;
+                    then(&mut self.proto, reply)?
+                }
+            };
+        }
+    }
+}
@@ -0,0 +1,508 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::breakpoint::{Breakpoint, BreakpointKind, Stop};
+use crate::calibration::{self, check_segment_a, is_erased, read_segment_a_backup};
+use crate::diagnostic::Severity;
+use crate::image::{compare, EraseRange, Image, Section};
+use crate::power::PowerProfile;
+use crate::protocol::{
+    append_hexdump, check_len, delete_failed, find_breakpoint, memory_write_error, mw_command,
+    parse_set_index, Protocol, MD_CHUNK_SIZE, MW_CHUNK_SIZE,
+};
+use crate::regs::{Register, Registers};
+use crate::tlv::{table_location, Calibration};
+use crate::{Error, GdbCfg};
+
+#[cfg(doc)]
+use crate::MspDebug;
+
+/// What an [`Op`] needs a session to do with `mspdebug`.
+pub(crate) enum Step {
+    /// Wait for `mspdebug` to become ready.
+    Ready,
+    /// Send a command, and wait until `mspdebug` started executing it.
+    Start(String),
+    /// Send a command, and collect its output until `mspdebug` is ready
+    /// again.
+    Command(String),
+    /// Let the target run for this long, reading what `mspdebug` prints in
+    /// the meantime if the session can.
+    Pause(Duration),
+    /// Wait for the running target to stop, collecting the registers
+    /// `mspdebug` prints. If set, interrupt it with `\break` first.
+    Stop(bool),
+}
+
+/// Output collected by a [`Step`]; empty unless it collects any.
+pub(crate) type Reply = Result<Vec<String>, Error>;
+
+type Then<T> = Box<dyn FnOnce(&mut Protocol, Reply) -> Result<Op<T>, Error> + Send>;
+type Next<T> = Box<dyn FnOnce(&mut Protocol) -> Result<Op<T>, Error> + Send>;
+
+/** A command to `mspdebug`, without the I/O.
+
+Made of [`Step`]s, each followed by what to do with its reply. Both sessions
+run an `Op` the same way, with only the steps themselves done over their own
+transport:
+
+```ignore
+loop {
+    op = match op {
+        Op::Done(val) => return Ok(val),
+        Op::Next(next) => next(&mut self.proto)?,
+        Op::Step(step, then) => {
+            let reply = self.exchange(step);
+            then(&mut self.proto, reply)?
+        }
+    };
+}
+```
+*/
+pub(crate) enum Op<T> {
+    Done(T),
+    /// Carry on without talking to `mspdebug`.
+    Next(Next<T>),
+    Step(Step, Then<T>),
+}
+
+impl<T: Send + 'static> Op<T> {
+    /// Carry on with `f`, which decides what to do from the session state.
+    fn with<F>(f: F) -> Self
+    where
+        F: FnOnce(&mut Protocol) -> Result<Op<T>, Error> + Send + 'static,
+    {
+        Op::Next(Box::new(f))
+    }
+
+    /// Do `step`, then carry on with `f` if it succeeded.
+    fn step<F>(step: Step, f: F) -> Self
+    where
+        F: FnOnce(&mut Protocol, Vec<String>) -> Result<Op<T>, Error> + Send + 'static,
+    {
+        Op::Step(step, Box::new(move |proto, reply| f(proto, reply?)))
+    }
+
+    /// Finish with `res`.
+    fn result(res: Result<T, Error>) -> Self {
+        match res {
+            Ok(val) => Op::Done(val),
+            Err(e) => Op::with(move |_| Err(e)),
+        }
+    }
+
+    /// Carry on with `f` once this is over, whether it succeeded or not.
+    fn finally<U, F>(self, f: F) -> Op<U>
+    where
+        U: Send + 'static,
+        F: FnOnce(&mut Protocol, Result<T, Error>) -> Result<Op<U>, Error> + Send + 'static,
+    {
+        match self {
+            Op::Done(val) => Op::with(move |proto| f(proto, Ok(val))),
+            Op::Next(next) => Op::with(move |proto| match next(proto) {
+                Ok(op) => Ok(op.finally(f)),
+                Err(e) => f(proto, Err(e)),
+            }),
+            Op::Step(step, then) => Op::Step(
+                step,
+                Box::new(move |proto, reply| match then(proto, reply) {
+                    Ok(op) => Ok(op.finally(f)),
+                    Err(e) => f(proto, Err(e)),
+                }),
+            ),
+        }
+    }
+
+    /// Carry on with `f` once this succeeded.
+    fn then<U, F>(self, f: F) -> Op<U>
+    where
+        U: Send + 'static,
+        F: FnOnce(&mut Protocol, T) -> Result<Op<U>, Error> + Send + 'static,
+    {
+        self.finally(move |proto, res| f(proto, res?))
+    }
+
+    /// Finish with `f` of the value this succeeded with.
+    fn and_then<U, F>(self, f: F) -> Op<U>
+    where
+        U: Send + 'static,
+        F: FnOnce(T) -> Result<U, Error> + Send + 'static,
+    {
+        self.then(move |_, val| f(val).map(Op::Done))
+    }
+}
+
+fn ready() -> Op<()> {
+    Op::step(Step::Ready, |_, _| Ok(Op::Done(())))
+}
+
+fn start(cmd: String) -> Op<()> {
+    Op::step(Step::Start(cmd), |_, _| Ok(Op::Done(())))
+}
+
+/// Run an arbitrary `mspdebug` command and return its normal output.
+pub(crate) fn command(cmd: String) -> Op<Vec<String>> {
+    Op::step(Step::Command(cmd), |_, output| Ok(Op::Done(output)))
+}
+
+/// [`command`], ignoring its output.
+fn exec(cmd: String) -> Op<()> {
+    command(cmd).and_then(|_| Ok(()))
+}
+
+pub(crate) fn reset() -> Op<()> {
+    exec("reset".to_owned())
+}
+
+pub(crate) fn erase() -> Op<()> {
+    protect_calibration(exec("erase".to_owned()))
+}
+
+pub(crate) fn erase_segrange(origin: u16, length: u16, sector_size: u16) -> Op<()> {
+    protect_calibration(erase_range(origin, length, sector_size))
+}
+
+fn erase_range(origin: u16, length: u16, sector_size: u16) -> Op<()> {
+    exec(format!(
+        "erase segrange {} {} {}",
+        origin, length, sector_size
+    ))
+}
+
+/// Erase each of `ranges` in turn.
+fn erase_ranges(mut ranges: Vec<EraseRange>) -> Op<()> {
+    if ranges.is_empty() {
+        return Op::Done(());
+    }
+
+    let (origin, length, sector_size) = ranges.remove(0);
+    erase_range(origin, length, sector_size).then(move |_, ()| Ok(erase_ranges(ranges)))
+}
+
+pub(crate) fn segment_a() -> Op<Vec<u8>> {
+    segment_a_location().then(|_, (addr, len)| Ok(read_memory(addr, len)))
+}
+
+pub(crate) fn write_segment_a(data: Vec<u8>) -> Op<()> {
+    segment_a_location().then(move |_, (addr, len)| {
+        check_segment_a(addr, len, &data)?;
+
+        Ok(erase_range(addr as u16, len as u16, len as u16)
+            .then(move |_, ()| Ok(write_memory(addr, data))))
+    })
+}
+
+pub(crate) fn restore_segment_a() -> Op<()> {
+    segment_a_location().then(|proto, (addr, _)| {
+        let data = read_segment_a_backup(proto.safeguard.clone(), proto.device.as_deref(), addr)?;
+        Ok(write_segment_a(data))
+    })
+}
+
+pub(crate) fn calibration() -> Op<Calibration> {
+    ready().then(|proto, ()| {
+        let device = proto.device.clone();
+        let (addr, len, _) = table_location(device.as_deref())?;
+
+        Ok(read_memory(addr, len).and_then(move |data| {
+            Calibration::parse(device.as_deref().unwrap_or_default(), addr, &data)
+        }))
+    })
+}
+
+fn segment_a_location() -> Op<(u32, usize)> {
+    ready().then(|proto, ()| {
+        let location = calibration::segment_a(proto.driver, proto.device.as_deref())?.ok_or(
+            Error::Unsupported {
+                driver: proto.driver,
+                operation: "access calibration data",
+            },
+        )?;
+
+        Ok(Op::Done(location))
+    })
+}
+
+/// Read (and back up) segment A before an erase, if
+/// [`Cfg::protect_calibration`](crate::Cfg::protect_calibration) is set.
+fn save_segment_a() -> Op<Option<(u32, Vec<u8>)>> {
+    Op::with(|proto| {
+        if proto.safeguard.is_none() {
+            return Ok(Op::Done(None));
+        }
+
+        Ok(ready().then(|proto, ()| {
+            let Some((addr, len)) = calibration::segment_a(proto.driver, proto.device.as_deref())?
+            else {
+                return Ok(Op::Done(None));
+            };
+
+            Ok(read_memory(addr, len).then(move |proto, data| {
+                if let (Some(s), Some(device)) = (&proto.safeguard, proto.device.as_deref()) {
+                    s.save(device, addr, &data)?;
+                }
+
+                Ok(Op::Done(Some((addr, data))))
+            }))
+        }))
+    })
+}
+
+/// Run `op`, which may erase flash, and write segment A back afterwards if
+/// `op` clobbered it.
+fn protect_calibration<T: Send + 'static>(op: Op<T>) -> Op<T> {
+    save_segment_a().then(move |_, saved| {
+        Ok(op.finally(move |_, res| {
+            let restore = match saved {
+                Some((addr, data)) if !is_erased(&data) => {
+                    read_memory(addr, data.len()).then(move |_, now| {
+                        if now == data {
+                            Ok(Op::Done(()))
+                        } else {
+                            Ok(write_segment_a(data))
+                        }
+                    })
+                }
+                _ => Op::Done(()),
+            };
+
+            Ok(restore.finally(move |_, restored| {
+                let val = res?;
+                restored.map(|_| Op::Done(val))
+            }))
+        }))
+    })
+}
+
+/// Read `len` bytes starting at `addr`, split into several `md` commands.
+pub(crate) fn read_memory(addr: u32, len: usize) -> Op<Vec<u8>> {
+    read_rest(addr, len, Vec::with_capacity(len))
+}
+
+fn read_rest(addr: u32, len: usize, mut data: Vec<u8>) -> Op<Vec<u8>> {
+    if data.len() >= len {
+        return Op::result(check_len(data, len));
+    }
+
+    let chunk_addr = addr + data.len() as u32;
+    let chunk_len = (len - data.len()).min(MD_CHUNK_SIZE);
+
+    command(format!("md 0x{:x} {}", chunk_addr, chunk_len)).then(move |_, output| {
+        append_hexdump(&mut data, addr, chunk_len, output)?;
+        Ok(read_rest(addr, len, data))
+    })
+}
+
+/// Write `data` starting at `addr`, split into several `mw` commands.
+pub(crate) fn write_memory(addr: u32, data: Vec<u8>) -> Op<()> {
+    write_rest(addr, data, 0)
+}
+
+fn write_rest(addr: u32, data: Vec<u8>, offset: usize) -> Op<()> {
+    if offset >= data.len() {
+        return Op::Done(());
+    }
+
+    let end = (offset + MW_CHUNK_SIZE).min(data.len());
+    let chunk_addr = addr + offset as u32;
+    let cmd = mw_command(chunk_addr, &data[offset..end]);
+
+    Op::Step(
+        Step::Command(cmd),
+        Box::new(move |_, reply| {
+            reply.map_err(|e| memory_write_error(e, chunk_addr))?;
+            Ok(write_rest(addr, data, end))
+        }),
+    )
+}
+
+/// Start the target, and return once `mspdebug` acknowledged it.
+pub(crate) fn run() -> Op<()> {
+    Op::with(|proto| {
+        proto.require_run_control("run the target")?;
+
+        Ok(start("run".to_owned()).then(|proto, ()| {
+            proto.running = true;
+            Ok(Op::Done(()))
+        }))
+    })
+}
+
+/// Halt the target and report where it stopped. If it isn't running, only
+/// the current registers are read.
+pub(crate) fn halt() -> Op<Stop> {
+    Op::with(|proto| {
+        if !proto.running {
+            return Ok(registers().then(|_, regs| Ok(stop_at(regs))));
+        }
+
+        Ok(finish_run(true))
+    })
+}
+
+/// Wait for the running target to stop, interrupting it if `interrupt` is
+/// set, and parse the registers `mspdebug` prints when it does.
+pub(crate) fn finish_run(interrupt: bool) -> Op<Stop> {
+    Op::Step(
+        Step::Stop(interrupt),
+        Box::new(|proto, reply| {
+            proto.running = false;
+            let regs = Registers::parse(&reply?)?;
+
+            Ok(stop_at(regs))
+        }),
+    )
+}
+
+fn stop_at(regs: Registers) -> Op<Stop> {
+    breakpoints().and_then(move |bps| Ok(Stop::at(regs, bps)))
+}
+
+/// Run until the CPU reaches `addr`, using a temporary breakpoint. A failure
+/// to delete the breakpoint afterwards is only a warning.
+pub(crate) fn run_until(addr: u32) -> Op<Stop> {
+    set_watchpoint(addr, BreakpointKind::Break).then(|_, index| {
+        let stop = run().then(|_, ()| Ok(finish_run(false)));
+
+        Ok(stop.finally(move |_, stop| {
+            Ok(delete_breakpoint(index).finally(move |proto, deleted| {
+                if let Err(e) = deleted {
+                    proto.push_diagnostic(Severity::Warning, delete_failed(index, &e));
+                }
+
+                Ok(Op::result(stop))
+            }))
+        }))
+    })
+}
+
+/// Run the target for `duration` while collecting power samples, then halt
+/// it again.
+pub(crate) fn profile_power(duration: Duration) -> Op<PowerProfile> {
+    Op::with(move |proto| {
+        proto.start_power_profile()?;
+
+        let sampled = run().then(move |_, ()| {
+            // Samples are collected from the lines mspdebug prints until it
+            // halts.
+            Ok(Op::step(Step::Pause(duration), |_, _| Ok(halt())))
+        });
+
+        Ok(sampled.finally(|proto, res| {
+            let profile = proto.finish_power_profile();
+            res?;
+
+            profile.map(Op::Done)
+        }))
+    })
+}
+
+pub(crate) fn step(count: u32) -> Op<Registers> {
+    Op::with(move |proto| {
+        proto.require_run_control("single-step the target")?;
+        Ok(command(format!("step {}", count)).and_then(|output| Registers::parse(&output)))
+    })
+}
+
+pub(crate) fn set_watchpoint(addr: u32, kind: BreakpointKind) -> Op<usize> {
+    Op::with(move |proto| {
+        proto.require_run_control("set breakpoints")?;
+
+        Ok(
+            command(format!("{} 0x{:x}", kind.command(), addr)).then(move |_, output| {
+                match parse_set_index(&output) {
+                    Some(index) => Ok(Op::Done(index)),
+                    None => Ok(breakpoints().and_then(move |bps| find_breakpoint(bps, addr, kind))),
+                }
+            }),
+        )
+    })
+}
+
+pub(crate) fn delete_breakpoint(index: usize) -> Op<()> {
+    exec(format!("delbreak {}", index))
+}
+
+pub(crate) fn clear_breakpoints() -> Op<()> {
+    exec("delbreak".to_owned())
+}
+
+pub(crate) fn breakpoints() -> Op<Vec<Breakpoint>> {
+    command("break".to_owned()).and_then(|output| Breakpoint::parse_table(&output))
+}
+
+pub(crate) fn registers() -> Op<Registers> {
+    command("regs".to_owned()).and_then(|output| Registers::parse(&output))
+}
+
+pub(crate) fn set_register(reg: Register, value: u32) -> Op<()> {
+    exec(format!("set {} 0x{:x}", reg.as_ref(), value))
+}
+
+/// Check `image` against the device, once `mspdebug` reported it, and
+/// return the Information Memory to erase before programming it.
+fn prepare(image: Image, allow_misfit: bool) -> Op<(Image, Vec<EraseRange>)> {
+    // Device info is printed before the first ready.
+    ready().then(move |proto, ()| {
+        let im = proto.prepare(&image, allow_misfit)?;
+        Ok(Op::Done((image, im)))
+    })
+}
+
+/// Program the ELF file at `filename`, then read it back if `verify` is set.
+pub(crate) fn program(filename: PathBuf, allow_misfit: bool, verify: bool) -> Op<()> {
+    Op::with(move |proto| {
+        if proto.group {
+            return Err(Error::ExpectedNoProcessGroup);
+        }
+
+        let image = Image::open(&filename)?;
+
+        Ok(prepare(image, allow_misfit).then(move |_, (image, im)| {
+            let prog = erase_ranges(im)
+                .then(move |_, ()| Ok(exec(format!("prog {}", filename.display()))));
+
+            Ok(protect_calibration(prog).then(move |_, ()| {
+                if verify {
+                    Ok(verify_sections(image.into_sections()))
+                } else {
+                    Ok(Op::Done(()))
+                }
+            }))
+        }))
+    })
+}
+
+/// Compare each of `sections` against target memory, at its load address.
+fn verify_sections(mut sections: Vec<Section>) -> Op<()> {
+    if sections.is_empty() {
+        return Op::Done(());
+    }
+
+    let s = sections.remove(0);
+    read_memory(s.addr, s.data.len()).then(move |_, actual| {
+        compare(s.addr, &s.data, &actual)?;
+        Ok(verify_sections(sections))
+    })
+}
+
+/// Start the `gdb` server and return the `msp430-elf-gdb` arguments.
+pub(crate) fn start_gdb(filename: PathBuf, cfg: GdbCfg) -> Op<Vec<String>> {
+    Op::with(move |proto| {
+        if !proto.group {
+            return Err(Error::ExpectedProcessGroup);
+        }
+        proto.require_run_control("run a gdb server")?;
+
+        let image = Image::open(&filename)?;
+
+        Ok(prepare(image, false).then(move |_, (_, im)| {
+            Ok(save_segment_a().then(move |_, seg_a| {
+                ctrlc::set_handler(move || {}).map_err(Error::CtrlCError)?;
+
+                Ok(start(format!("gdb {}", cfg.port))
+                    .and_then(move |()| Ok(cfg.args(&filename, im, seg_a))))
+            }))
+        }))
+    })
+}
@@ -0,0 +1,197 @@
+use std::io;
+use std::time::Duration;
+
+use crate::protocol::ShellType;
+
+/// Supply voltage `mspdebug` sets when none is given with `-v`, in millivolts.
+pub(crate) const DEFAULT_MILLIVOLTS: u32 = 3000;
+
+/// One raw power sample, as sent in a `\power-samples` shell message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PowerSample {
+    /// Memory address bus, i.e. roughly where the CPU was executing.
+    pub mab: u32,
+    /// Current drawn by the target, in microamps.
+    pub current_ua: u32,
+}
+
+impl PowerSample {
+    /** Decode the payload of a `\power-samples` message: base64-encoded
+    samples of 8 bytes each, a little-endian 32-bit MAB followed by the
+    little-endian 32-bit current in microamps.
+    */
+    pub(crate) fn decode(data: &str) -> Option<Vec<PowerSample>> {
+        let bytes = base64_decode(data)?;
+        if bytes.len() % 8 != 0 {
+            return None;
+        }
+
+        let word = |b: &[u8]| u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+        let samples = bytes
+            .chunks_exact(8)
+            .map(|s| PowerSample {
+                mab: word(&s[..4]),
+                current_ua: word(&s[4..]),
+            })
+            .collect();
+
+        Some(samples)
+    }
+}
+
+/// A [`PowerSample`] placed in time, see [`PowerProfile::readings`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PowerReading {
+    /// Time since the target started running.
+    pub time: Duration,
+    pub mab: u32,
+    pub current_ua: u32,
+    /// Energy used by the target up to and including this sample, in
+    /// microjoules.
+    pub energy_uj: f64,
+}
+
+/** Power samples recorded while the target ran, see
+[`MspDebug::profile_power`](crate::MspDebug::profile_power).
+
+`mspdebug` only reports current; energy is derived from the supply voltage
+the session was started with (see [`Cfg::voltage`](crate::Cfg::voltage)),
+or `mspdebug`'s default of 3.0 V.
+*/
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PowerProfile {
+    sample_period: Duration,
+    millivolts: u32,
+    samples: Vec<PowerSample>,
+}
+
+impl PowerProfile {
+    pub(crate) fn new(millivolts: u32) -> Self {
+        Self {
+            sample_period: Duration::ZERO,
+            millivolts,
+            samples: Vec::new(),
+        }
+    }
+
+    /// Collect the sample period and samples from a shell message.
+    pub(crate) fn record(&mut self, shell: &ShellType) {
+        match shell {
+            ShellType::PowerSampleUs(us) => {
+                self.sample_period = Duration::from_micros((*us).into());
+            }
+            ShellType::PowerSamples(s) => self.samples.extend_from_slice(s),
+            _ => {}
+        }
+    }
+
+    /// Time between two samples.
+    pub fn sample_period(&self) -> Duration {
+        self.sample_period
+    }
+
+    /// Supply voltage used to compute energy, in millivolts.
+    pub fn millivolts(&self) -> u32 {
+        self.millivolts
+    }
+
+    pub fn samples(&self) -> &[PowerSample] {
+        &self.samples
+    }
+
+    /// Time covered by the samples.
+    pub fn duration(&self) -> Duration {
+        self.sample_period * self.samples.len() as u32
+    }
+
+    /// The samples with their time and the energy used so far.
+    pub fn readings(&self) -> impl Iterator<Item = PowerReading> + '_ {
+        let period = self.sample_period;
+        let volts = f64::from(self.millivolts) / 1000.0;
+
+        self.samples
+            .iter()
+            .enumerate()
+            .scan(0.0, move |energy_uj, (i, s)| {
+                *energy_uj += f64::from(s.current_ua) * volts * period.as_secs_f64();
+
+                Some(PowerReading {
+                    time: period * i as u32,
+                    mab: s.mab,
+                    current_ua: s.current_ua,
+                    energy_uj: *energy_uj,
+                })
+            })
+    }
+
+    /// Total energy used by the target, in microjoules.
+    pub fn energy_uj(&self) -> f64 {
+        self.readings().last().map_or(0.0, |r| r.energy_uj)
+    }
+
+    /// Average current drawn by the target, in microamps.
+    pub fn average_current_ua(&self) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+
+        let total: u64 = self.samples.iter().map(|s| u64::from(s.current_ua)).sum();
+        total as f64 / self.samples.len() as f64
+    }
+
+    /** Write the readings as CSV, with a header row:
+
+    ```text
+    time_us,mab,current_ua,energy_uj
+    0,0xc000,312,0.936000
+    1000,0xc004,298,1.830000
+    ```
+    */
+    pub fn write_csv<W>(&self, mut w: W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        writeln!(w, "time_us,mab,current_ua,energy_uj")?;
+
+        for r in self.readings() {
+            writeln!(
+                w,
+                "{},0x{:04x},{},{:.6}",
+                r.time.as_micros(),
+                r.mab,
+                r.current_ua,
+                r.energy_uj
+            )?;
+        }
+
+        w.flush()
+    }
+}
+
+/// Decode standard (RFC 4648) base64, as produced by `mspdebug`.
+fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim_end_matches('=');
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    let mut acc = 0u32;
+    let mut bits = 0;
+
+    for c in text.bytes() {
+        let v = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+
+        acc = (acc << 6) | u32::from(v);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+
+    Some(out)
+}
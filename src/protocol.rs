@@ -0,0 +1,447 @@
+use std::collections::VecDeque;
+use std::process::ExitStatus;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::breakpoint::{Breakpoint, BreakpointKind};
+use crate::calibration::Safeguard;
+use crate::diagnostic::{Diagnostic, DiagnosticCallback, Severity};
+use crate::error::OutputTail;
+use crate::event::Subscribers;
+use crate::image::{EraseRange, Image};
+use crate::power::{PowerProfile, PowerSample};
+use crate::transcript::Recorder;
+use crate::{Cfg, Error, TargetDriver};
+
+#[cfg(doc)]
+use crate::ops::Op;
+#[cfg(doc)]
+use crate::MspDebug;
+
+pub(crate) enum OutputType<'a> {
+    Normal(&'a str),
+    Debug(&'a str),
+    Error(ErrorSeverity<'a>),
+    Shell(ShellType),
+}
+
+pub(crate) enum ErrorSeverity<'a> {
+    Warning(&'a str),
+    Error(&'a str),
+}
+
+/// A shell message (`\`), reporting a change of `mspdebug`'s state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShellType {
+    /// `mspdebug` is ready to accept a command.
+    Ready,
+    /// `mspdebug` started executing a command.
+    Busy,
+    /// The target runs with power profiling; samples are this many
+    /// microseconds apart.
+    PowerSampleUs(u32),
+    /// A block of power samples taken while the target runs.
+    PowerSamples(Vec<PowerSample>),
+}
+
+/// Largest read issued with a single `md` command. Matches the buffer size
+/// `mspdebug` itself uses when dumping memory.
+pub(crate) const MD_CHUNK_SIZE: usize = 4096;
+
+/// Most bytes written with a single `mw` command, so that each command stays
+/// well below `mspdebug`'s input line limit.
+pub(crate) const MW_CHUNK_SIZE: usize = 64;
+
+/// Lines of stdout kept around for [`Error::ChildExited`].
+pub(crate) const TAIL_LINES: usize = 16;
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum WaitMode {
+    Ready,
+    Busy,
+}
+
+/// The last few lines of a stream, shared with the thread reading it.
+#[derive(Clone)]
+pub(crate) struct Tail {
+    lines: Arc<Mutex<VecDeque<String>>>,
+    capacity: usize,
+}
+
+impl Tail {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    pub(crate) fn push(&self, line: &str) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut lines = self.lines.lock().unwrap();
+
+        if lines.len() == self.capacity {
+            lines.pop_front();
+        }
+        lines.push_back(line.trim_end().to_owned());
+    }
+
+    pub(crate) fn lines(&self) -> Vec<String> {
+        self.lines.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+/** What a session knows about `mspdebug` and the target, and how it makes
+sense of what `mspdebug` prints.
+
+Shared by [`MspDebug`] and the async session, which only differ in how they
+exchange lines with `mspdebug`: every line read is handed to
+[`Protocol::feed`], and commands are built as an [`Op`] that the session runs
+over its transport.
+*/
+pub(crate) struct Protocol {
+    pub(crate) driver: TargetDriver,
+    /// [`Cfg::group`]: whether `mspdebug` was asked to run in its own process
+    /// group.
+    pub(crate) group: bool,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) safeguard: Option<Safeguard>,
+    millivolts: u32,
+    last_shelltype: Option<ShellType>,
+    pub(crate) device: Option<String>,
+    /// The target runs, and `mspdebug` won't accept commands until it is
+    /// halted.
+    pub(crate) running: bool,
+    stdout_tail: Tail,
+    pub(crate) stderr_tail: Tail,
+    pub(crate) diagnostics: Vec<Diagnostic>,
+    pub(crate) on_diagnostic: Option<DiagnosticCallback>,
+    pub(crate) subscribers: Subscribers,
+    /// Samples collected while profiling power.
+    pub(crate) power: Option<PowerProfile>,
+    recorder: Option<Recorder>,
+}
+
+impl Protocol {
+    pub(crate) fn new(cfg: &Cfg, stderr_tail: Tail, recorder: Option<Recorder>) -> Self {
+        Self {
+            driver: cfg.driver,
+            group: cfg.group,
+            timeout: cfg.timeout,
+            safeguard: cfg.safeguard(),
+            millivolts: cfg.millivolts(),
+            last_shelltype: None,
+            device: None,
+            running: false,
+            stdout_tail: Tail::new(TAIL_LINES),
+            stderr_tail,
+            diagnostics: Vec::new(),
+            on_diagnostic: None,
+            subscribers: Subscribers::default(),
+            power: None,
+            recorder,
+        }
+    }
+
+    /// Whether a wait for `mode` is already over. Every command waits for
+    /// ready at the beginning and end, so the last shell message is cached.
+    pub(crate) fn waited(&self, mode: WaitMode) -> bool {
+        matches!(
+            (&self.last_shelltype, mode),
+            (Some(ShellType::Ready), WaitMode::Ready) | (Some(ShellType::Busy), WaitMode::Busy)
+        )
+    }
+
+    /** Handle a line `mspdebug` printed while waiting for `mode`, and return
+    whether the wait is over.
+
+    Subscribers and the power profile being recorded see every line.
+    Warnings and errors `mspdebug` recovers from become diagnostics; any other
+    error is returned as [`Error::CommsError`]. Normal output is appended to
+    `output`, and the device name is picked up from it.
+    */
+    pub(crate) fn feed(
+        &mut self,
+        line: &str,
+        mode: WaitMode,
+        output: Option<&mut Vec<String>>,
+    ) -> Result<bool, Error> {
+        self.stdout_tail.push(line);
+        if let Some(r) = self.recorder.as_mut() {
+            r.output(line);
+        }
+        self.last_shelltype = None;
+
+        let out = classify(line)?;
+        self.subscribers.publish(&out);
+        if let (OutputType::Shell(s), Some(p)) = (&out, self.power.as_mut()) {
+            p.record(s);
+        }
+
+        match out {
+            OutputType::Shell(ShellType::Ready) if mode == WaitMode::Ready => {
+                self.last_shelltype = Some(ShellType::Ready);
+                return Ok(true);
+            }
+            OutputType::Shell(ShellType::Busy) if mode == WaitMode::Busy => {
+                self.last_shelltype = Some(ShellType::Busy);
+                return Ok(true);
+            }
+            OutputType::Error(ErrorSeverity::Warning(w)) => {
+                self.push_diagnostic(Severity::Warning, w.trim_end().to_owned());
+            }
+            OutputType::Error(ErrorSeverity::Error(e)) if is_ignored_error(e) => {
+                self.push_diagnostic(Severity::IgnoredError, e.trim_end().to_owned());
+            }
+            OutputType::Error(ErrorSeverity::Error(e)) => {
+                return Err(Error::CommsError {
+                    msg: e.trim_end().into(),
+                    stderr: self.stderr_tail.lines(),
+                });
+            }
+            OutputType::Normal(n) => {
+                if let (Some(d), None) = (parse_device(n), &self.device) {
+                    self.device = Some(d);
+                }
+
+                if let Some(out) = output {
+                    out.push(n.trim_end().to_owned());
+                }
+            }
+            _ => {}
+        }
+
+        Ok(false)
+    }
+
+    /// Record `input` sent to `mspdebug`.
+    pub(crate) fn sent(&mut self, input: &[u8]) {
+        if let Some(r) = self.recorder.as_mut() {
+            r.input(input);
+        }
+    }
+
+    /// `mspdebug` closed stdout; `status` is how it exited, if known.
+    pub(crate) fn child_exited(&self, status: Option<ExitStatus>) -> Error {
+        Error::ChildExited {
+            status,
+            last_output: OutputTail {
+                stdout: self.stdout_tail.lines(),
+                stderr: self.stderr_tail.lines(),
+            },
+        }
+    }
+
+    pub(crate) fn push_diagnostic(&mut self, severity: Severity, message: String) {
+        let diag = Diagnostic { severity, message };
+
+        if let Some(cb) = self.on_diagnostic.as_mut() {
+            cb(&diag);
+        }
+
+        self.diagnostics.push(diag);
+    }
+
+    /// Bootloader drivers can read and write memory, but not control the CPU.
+    pub(crate) fn require_run_control(&self, operation: &'static str) -> Result<(), Error> {
+        if self.driver.capabilities().bsl_only {
+            return Err(Error::Unsupported {
+                driver: self.driver,
+                operation,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Start collecting power samples, if the driver sends any.
+    pub(crate) fn start_power_profile(&mut self) -> Result<(), Error> {
+        if !self.driver.capabilities().power_profiling {
+            return Err(Error::Unsupported {
+                driver: self.driver,
+                operation: "profile power",
+            });
+        }
+
+        self.power = Some(PowerProfile::new(self.millivolts));
+        Ok(())
+    }
+
+    /// Stop collecting power samples, and return them.
+    pub(crate) fn finish_power_profile(&mut self) -> Result<PowerProfile, Error> {
+        match self.power.take() {
+            Some(p) if !p.samples().is_empty() => Ok(p),
+            _ => Err(Error::NoPowerSamples),
+        }
+    }
+
+    /// Check `image` against the device, and return the Information Memory
+    /// to erase before programming it.
+    pub(crate) fn prepare(
+        &mut self,
+        image: &Image,
+        allow_misfit: bool,
+    ) -> Result<Vec<EraseRange>, Error> {
+        let (im, warning) = image.prepare(self.driver, self.device.as_deref(), allow_misfit)?;
+
+        if let Some(w) = warning {
+            self.push_diagnostic(Severity::Warning, w);
+        }
+
+        Ok(im)
+    }
+}
+
+/** Parse one line of `mspdebug`'s hex dump format into its address and data.
+
+```text
+    0c000: 31 40 00 04 3f 40 00 00 3f 90 00 00 05 24 1e 42 |1@..?@..?....$.B|
+```
+*/
+pub(crate) fn parse_hexdump_line(line: &str) -> Option<(u32, Vec<u8>)> {
+    let (addr, rest) = line.trim_start().split_once(':')?;
+    let addr = u32::from_str_radix(addr, 16).ok()?;
+
+    let bytes = rest
+        .split_whitespace()
+        .take_while(|b| !b.starts_with('|'))
+        .map(|b| u8::from_str_radix(b, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+
+    Some((addr, bytes))
+}
+
+/// Split an embedded-mode line into its sigil and message.
+pub(crate) fn classify(line: &str) -> Result<OutputType<'_>, Error> {
+    let msg = line.get(1..).unwrap_or_default();
+
+    match line.chars().next() {
+        Some(':') => Ok(OutputType::Normal(msg)),
+        Some('-') => Ok(OutputType::Debug(msg)),
+        Some('!') if msg.starts_with("warning") => {
+            Ok(OutputType::Error(ErrorSeverity::Warning(msg)))
+        }
+        Some('!') => Ok(OutputType::Error(ErrorSeverity::Error(msg))),
+        Some('\\') => Ok(OutputType::Shell(shell_type(msg)?)),
+        Some(un) => Err(Error::UnexpectedSigil(un)),
+        None => unreachable!(),
+    }
+}
+
+/// Parse a shell message. The power profiling messages carry an argument,
+/// e.g. `\power-sample-us 1000`.
+fn shell_type(line: &str) -> Result<ShellType, Error> {
+    let msg = line.trim_end_matches(['\r', '\n']);
+    let (name, arg) = msg.split_once(' ').unwrap_or((msg, ""));
+
+    let shell = match (name, arg) {
+        ("ready", "") => Some(ShellType::Ready),
+        ("busy", "") => Some(ShellType::Busy),
+        ("power-sample-us", us) => us.parse().ok().map(ShellType::PowerSampleUs),
+        ("power-samples", data) => PowerSample::decode(data).map(ShellType::PowerSamples),
+        _ => None,
+    };
+
+    shell.ok_or_else(|| Error::UnexpectedShellMessage(line.to_owned()))
+}
+
+/// Errors that `mspdebug` recovers from by itself.
+fn is_ignored_error(e: &str) -> bool {
+    e.starts_with("fet: FET returned error code")
+        || e.starts_with("fet: command C_IDENT1 failed")
+        || e.starts_with("fet: FET returned NAK")
+}
+
+/// The device name from the `Device: ` line printed when the device is opened.
+fn parse_device(line: &str) -> Option<String> {
+    line.strip_prefix("Device: ")
+        .map(|d| d.trim_end().to_owned())
+}
+
+/// Append the output of `md` for `len` bytes to `data`, which holds what was
+/// read so far starting at `addr`.
+pub(crate) fn append_hexdump(
+    data: &mut Vec<u8>,
+    addr: u32,
+    len: usize,
+    output: Vec<String>,
+) -> Result<(), Error> {
+    let chunk_addr = addr + data.len() as u32;
+    let start = data.len();
+
+    for line in output {
+        let (line_addr, bytes) =
+            parse_hexdump_line(&line).ok_or_else(|| Error::UnexpectedOutput(line.clone()))?;
+
+        if line_addr != addr + data.len() as u32 {
+            return Err(Error::UnexpectedOutput(line));
+        }
+
+        data.extend(bytes);
+    }
+
+    if data.len() == start {
+        return Err(Error::UnexpectedOutput(format!(
+            "empty dump for md 0x{:x} {}",
+            chunk_addr, len
+        )));
+    }
+
+    Ok(())
+}
+
+pub(crate) fn check_len(data: Vec<u8>, len: usize) -> Result<Vec<u8>, Error> {
+    if data.len() != len {
+        return Err(Error::UnexpectedOutput(format!(
+            "expected {} bytes from md, got {}",
+            len,
+            data.len()
+        )));
+    }
+
+    Ok(data)
+}
+
+pub(crate) fn mw_command(addr: u32, data: &[u8]) -> String {
+    let mut cmd = format!("mw 0x{:x}", addr);
+    for b in data {
+        cmd.push_str(&format!(" 0x{:02x}", b));
+    }
+
+    cmd
+}
+
+pub(crate) fn memory_write_error(e: Error, addr: u32) -> Error {
+    match e {
+        Error::CommsError { msg, .. } => Error::MemoryWriteError { addr, msg },
+        e => e,
+    }
+}
+
+/// The index in `setbreak`'s "Set breakpoint 0" / "Set watchpoint 1" output.
+pub(crate) fn parse_set_index(output: &[String]) -> Option<usize> {
+    output
+        .iter()
+        .filter_map(|l| l.trim().strip_prefix("Set "))
+        .find_map(|l| l.split_whitespace().find_map(|t| t.parse().ok()))
+}
+
+pub(crate) fn find_breakpoint(
+    bps: Vec<Breakpoint>,
+    addr: u32,
+    kind: BreakpointKind,
+) -> Result<usize, Error> {
+    bps.into_iter()
+        .find(|b| b.addr == addr && b.kind == kind)
+        .map(|b| b.index)
+        .ok_or_else(|| Error::UnexpectedOutput(format!("breakpoint at 0x{:x} was not set", addr)))
+}
+
+/// Warning for a temporary breakpoint that [`MspDebug::run_until`] couldn't
+/// remove.
+pub(crate) fn delete_failed(index: usize, e: &Error) -> String {
+    format!("could not delete temporary breakpoint {}: {}", index, e)
+}
@@ -0,0 +1,182 @@
+use std::fmt;
+
+use bitflags::bitflags;
+use strum_macros::AsRefStr;
+
+use super::Error;
+
+/// CPU registers, named the way `mspdebug` prints them in `regs`.
+#[derive(Clone, Copy, Debug, AsRefStr, PartialEq, Eq)]
+pub enum Register {
+    /// R0, program counter
+    #[strum(serialize = "PC")]
+    Pc,
+    /// R1, stack pointer
+    #[strum(serialize = "SP")]
+    Sp,
+    /// R2, status register
+    #[strum(serialize = "SR")]
+    Sr,
+    /// R3, constant generator
+    #[strum(serialize = "R3")]
+    Cg,
+    #[strum(serialize = "R4")]
+    R4,
+    #[strum(serialize = "R5")]
+    R5,
+    #[strum(serialize = "R6")]
+    R6,
+    #[strum(serialize = "R7")]
+    R7,
+    #[strum(serialize = "R8")]
+    R8,
+    #[strum(serialize = "R9")]
+    R9,
+    #[strum(serialize = "R10")]
+    R10,
+    #[strum(serialize = "R11")]
+    R11,
+    #[strum(serialize = "R12")]
+    R12,
+    #[strum(serialize = "R13")]
+    R13,
+    #[strum(serialize = "R14")]
+    R14,
+    #[strum(serialize = "R15")]
+    R15,
+}
+
+impl Register {
+    const ALL: [Register; 16] = [
+        Register::Pc,
+        Register::Sp,
+        Register::Sr,
+        Register::Cg,
+        Register::R4,
+        Register::R5,
+        Register::R6,
+        Register::R7,
+        Register::R8,
+        Register::R9,
+        Register::R10,
+        Register::R11,
+        Register::R12,
+        Register::R13,
+        Register::R14,
+        Register::R15,
+    ];
+
+    /// Register by number, `0` (PC) through `15`.
+    pub fn from_index(idx: usize) -> Option<Register> {
+        Self::ALL.get(idx).copied()
+    }
+
+    fn from_name(name: &str) -> Option<Register> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|r| r.as_ref().eq_ignore_ascii_case(name))
+    }
+}
+
+bitflags! {
+    /// Decoded contents of the status register (R2).
+    pub struct StatusFlags: u16 {
+        const C = 1;
+        const Z = 1 << 1;
+        const N = 1 << 2;
+        const GIE = 1 << 3;
+        const CPUOFF = 1 << 4;
+        const OSCOFF = 1 << 5;
+        const SCG0 = 1 << 6;
+        const SCG1 = 1 << 7;
+        const V = 1 << 8;
+    }
+}
+
+/** Snapshot of R0-R15 as returned by [`MspDebug::registers`](crate::MspDebug::registers).
+
+Values are stored as `u32` so that the 20-bit registers of CPUX devices fit;
+on plain CPU devices the upper bits are always zero.
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Registers {
+    regs: [u32; 16],
+}
+
+impl Registers {
+    pub fn get(&self, reg: Register) -> u32 {
+        self.regs[reg as usize]
+    }
+
+    pub fn pc(&self) -> u32 {
+        self.get(Register::Pc)
+    }
+
+    pub fn sp(&self) -> u32 {
+        self.get(Register::Sp)
+    }
+
+    pub fn sr(&self) -> StatusFlags {
+        StatusFlags::from_bits_truncate(self.get(Register::Sr) as u16)
+    }
+
+    pub fn cg(&self) -> u32 {
+        self.get(Register::Cg)
+    }
+
+    /** Parse the register table printed by `regs` (and after `step`/`run`).
+
+    ```text
+        ( PC: 0c0b4)  ( R4: 0ff7a)  ( R8: 0dfff)  (R12: 00000)
+        ( SP: 003fe)  ( R5: 05a08)  ( R9: 0ffbf)  (R13: 0ffff)
+        ( SR: 00000)  ( R6: 0ffff)  (R10: 0fffd)  (R14: 0ffff)
+        ( R3: 00000)  ( R7: 0ffff)  (R11: 0ffff)  (R15: 0ff7f)
+    ```
+
+    Lines that are not part of the table, such as the disassembly that
+    follows it, are ignored.
+    */
+    pub(crate) fn parse(lines: &[String]) -> Result<Registers, Error> {
+        let mut regs = [None; 16];
+
+        for line in lines.iter().filter(|l| l.trim_start().starts_with('(')) {
+            for cell in line.split('(').skip(1) {
+                let (name, value) = cell
+                    .split_once(')')
+                    .and_then(|(c, _)| c.split_once(':'))
+                    .ok_or_else(|| Error::UnexpectedOutput(line.clone()))?;
+
+                let reg = Register::from_name(name.trim())
+                    .ok_or_else(|| Error::UnexpectedOutput(line.clone()))?;
+                let value = u32::from_str_radix(value.trim(), 16)
+                    .map_err(|_| Error::UnexpectedOutput(line.clone()))?;
+
+                regs[reg as usize] = Some(value);
+            }
+        }
+
+        let mut out = [0; 16];
+        for (i, r) in regs.iter().enumerate() {
+            out[i] = r.ok_or_else(|| {
+                Error::UnexpectedOutput(format!("register {} missing from regs", i))
+            })?;
+        }
+
+        Ok(Registers { regs: out })
+    }
+}
+
+impl fmt::Display for Registers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..4 {
+            for col in 0..4 {
+                let reg = Register::ALL[col * 4 + row];
+                write!(f, "({:>3}: {:05x})  ", reg.as_ref(), self.get(reg))?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
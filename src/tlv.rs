@@ -0,0 +1,347 @@
+use crate::driver::infomem_layout;
+use crate::Error;
+
+/// Start of the device descriptor table on 5xx, 6xx and FRAM parts.
+const DESCRIPTOR_ADDR: u32 = 0x1a00;
+/// Bytes read for the device descriptor table; the table is shorter on
+/// most parts and ends with an end tag.
+const DESCRIPTOR_LEN: usize = 0x100;
+
+/// Segment A tags on 2xx parts.
+const TAG_DCO_30: u8 = 0x01;
+const TAG_ADC12_1: u8 = 0x08;
+const TAG_ADC10_1: u8 = 0x10;
+
+/// Device descriptor tags on 5xx, 6xx and FRAM parts.
+const TLV_DIERECORD: u8 = 0x08;
+const TLV_ADC12CAL: u8 = 0x11;
+const TLV_REFCAL: u8 = 0x12;
+const TLV_TAGEND: u8 = 0xff;
+
+/// Where the calibration table of a device lives, and how it's laid out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TableKind {
+    /// 2xx: Information Memory segment A, guarded by an XOR checksum.
+    SegmentA,
+    /// 5xx, 6xx and FRAM parts: the device descriptor table, guarded by a
+    /// CRC.
+    Descriptor,
+}
+
+/** Address, length and layout of the calibration table of `device`.
+
+Parts with 64-byte Information Memory segments at `0x1000` (2xx, and some 4xx)
+keep calibration in segment A; parts with Information Memory at `0x1800` have
+a device descriptor table at `0x1a00`. 1xx parts have no calibration table.
+*/
+pub(crate) fn table_location(device: Option<&str>) -> Result<(u32, usize, TableKind), Error> {
+    let (origin, length, sector_size) = infomem_layout(device)?;
+
+    match (origin, sector_size) {
+        (0x1000, 64) => {
+            let addr = u32::from(origin) + u32::from(length - sector_size);
+            Ok((addr, sector_size.into(), TableKind::SegmentA))
+        }
+        (0x1800, _) => Ok((DESCRIPTOR_ADDR, DESCRIPTOR_LEN, TableKind::Descriptor)),
+        _ => Err(Error::UnknownDevice(device.unwrap_or_default().to_owned())),
+    }
+}
+
+/// One tag-length-value entry of a calibration table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TlvEntry {
+    pub tag: u8,
+    /// Address of the value, after the tag and length bytes.
+    pub addr: u32,
+    pub value: Vec<u8>,
+}
+
+/// Factory DCO calibration for one frequency (`CALDCO_xMHZ`/`CALBC1_xMHZ`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DcoCal {
+    pub mhz: u8,
+    /// Value for `DCOCTL`.
+    pub caldco: u8,
+    /// Value for `BCSCTL1`.
+    pub calbc1: u8,
+}
+
+/// ADC readings of the temperature sensor at 30 and 85 °C, for one
+/// reference voltage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TempSensorCal {
+    pub ref_mv: u16,
+    pub t30: u16,
+    pub t85: u16,
+}
+
+/// Correction factor for one internal reference voltage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RefCal {
+    pub ref_mv: u16,
+    pub factor: u16,
+}
+
+/// ADC gain and offset correction, and temperature sensor calibration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AdcCal {
+    pub gain_factor: u16,
+    pub offset: i16,
+    pub temp_sensor: Vec<TempSensorCal>,
+}
+
+/// Where a 5xx/6xx/FRAM die sat on its wafer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DieRecord {
+    pub lot_wafer_id: u32,
+    pub die_x: u16,
+    pub die_y: u16,
+    pub test_results: u16,
+}
+
+/** The factory calibration of a device, see
+[`MspDebug::calibration`](crate::MspDebug::calibration).
+
+2xx parts store DCO and ADC calibration in Information Memory segment A.
+5xx, 6xx and FRAM parts store ADC and reference calibration and a die record
+in the device descriptor table at `0x1a00`. Either table's checksum is checked
+before it is decoded.
+*/
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Calibration {
+    /// Device ID from the descriptor table header (5xx and later).
+    pub device_id: Option<u16>,
+    pub hw_revision: Option<u8>,
+    pub fw_revision: Option<u8>,
+    pub dco: Vec<DcoCal>,
+    pub adc: Option<AdcCal>,
+    pub reference: Vec<RefCal>,
+    pub die: Option<DieRecord>,
+    /// Every entry of the table, including those not decoded above.
+    pub entries: Vec<TlvEntry>,
+}
+
+fn word(b: &[u8], i: usize) -> u16 {
+    u16::from_le_bytes([b[2 * i], b[2 * i + 1]])
+}
+
+impl Calibration {
+    /// Decode the calibration table of `device`, read from `addr`.
+    pub(crate) fn parse(device: &str, addr: u32, data: &[u8]) -> Result<Self, Error> {
+        let (_, _, kind) = table_location(Some(device))?;
+
+        match kind {
+            TableKind::SegmentA => Self::parse_segment_a(addr, data),
+            TableKind::Descriptor => {
+                // FRAM parts have a 1.2 V reference where others have 1.5 V.
+                let first_ref_mv = if device.starts_with("MSP430FR") {
+                    1200
+                } else {
+                    1500
+                };
+
+                Self::parse_descriptor(addr, data, first_ref_mv)
+            }
+        }
+    }
+
+    /** Decode segment A of a 2xx part.
+
+    The first word is the two's complement of the XOR of all other words;
+    tag-length-value entries follow.
+    */
+    fn parse_segment_a(addr: u32, data: &[u8]) -> Result<Self, Error> {
+        check_segment_a_checksum(addr, data)?;
+
+        let mut cal = Calibration {
+            entries: entries(addr, data, 2, None),
+            ..Default::default()
+        };
+
+        for e in &cal.entries {
+            let v = &e.value;
+            match e.tag {
+                TAG_DCO_30 if v.len() >= 8 => {
+                    cal.dco = [16, 12, 8, 1]
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &mhz)| DcoCal {
+                            mhz,
+                            caldco: v[2 * i],
+                            calbc1: v[2 * i + 1],
+                        })
+                        .collect();
+                }
+                TAG_ADC10_1 | TAG_ADC12_1 if v.len() >= 16 => {
+                    cal.adc = Some(AdcCal {
+                        gain_factor: word(v, 0),
+                        offset: word(v, 1) as i16,
+                        temp_sensor: vec![
+                            TempSensorCal {
+                                ref_mv: 1500,
+                                t30: word(v, 3),
+                                t85: word(v, 4),
+                            },
+                            TempSensorCal {
+                                ref_mv: 2500,
+                                t30: word(v, 6),
+                                t85: word(v, 7),
+                            },
+                        ],
+                    });
+                    cal.reference = vec![
+                        RefCal {
+                            ref_mv: 1500,
+                            factor: word(v, 2),
+                        },
+                        RefCal {
+                            ref_mv: 2500,
+                            factor: word(v, 5),
+                        },
+                    ];
+                }
+                _ => {}
+            }
+        }
+
+        Ok(cal)
+    }
+
+    /** Decode the device descriptor table of a 5xx, 6xx or FRAM part.
+
+    The header holds the table and CRC lengths (as powers of two, in 32-bit
+    words), the CRC of the rest of the CRC-covered area, and the device ID and
+    revisions; tag-length-value entries start at offset 8 and end with an end
+    tag.
+    */
+    fn parse_descriptor(addr: u32, data: &[u8], first_ref_mv: u16) -> Result<Self, Error> {
+        if data.len() < 8 {
+            return Err(Error::UnexpectedOutput(format!(
+                "device descriptor table is {} bytes",
+                data.len()
+            )));
+        }
+
+        let covered = 4usize
+            .checked_shl(data[1].into())
+            .and_then(|crc_len| data.get(4..crc_len))
+            .ok_or_else(|| {
+                Error::UnexpectedOutput(format!("device descriptor CRC length {}", data[1]))
+            })?;
+        check_checksum(addr, crc16(covered), word(data, 1))?;
+
+        let mut cal = Calibration {
+            device_id: Some(u16::from_be_bytes([data[4], data[5]])),
+            hw_revision: Some(data[6]),
+            fw_revision: Some(data[7]),
+            entries: entries(addr, data, 8, Some(TLV_TAGEND)),
+            ..Default::default()
+        };
+        let refs = [first_ref_mv, 2000, 2500];
+
+        for e in &cal.entries {
+            let v = &e.value;
+            match e.tag {
+                TLV_DIERECORD if v.len() >= 10 => {
+                    cal.die = Some(DieRecord {
+                        lot_wafer_id: u32::from(word(v, 0)) | u32::from(word(v, 1)) << 16,
+                        die_x: word(v, 2),
+                        die_y: word(v, 3),
+                        test_results: word(v, 4),
+                    });
+                }
+                TLV_ADC12CAL if v.len() >= 16 => {
+                    cal.adc = Some(AdcCal {
+                        gain_factor: word(v, 0),
+                        offset: word(v, 1) as i16,
+                        temp_sensor: refs
+                            .iter()
+                            .enumerate()
+                            .map(|(i, &ref_mv)| TempSensorCal {
+                                ref_mv,
+                                t30: word(v, 2 + 2 * i),
+                                t85: word(v, 3 + 2 * i),
+                            })
+                            .collect(),
+                    });
+                }
+                TLV_REFCAL if v.len() >= 6 => {
+                    cal.reference = refs
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &ref_mv)| RefCal {
+                            ref_mv,
+                            factor: word(v, i),
+                        })
+                        .collect();
+                }
+                _ => {}
+            }
+        }
+
+        Ok(cal)
+    }
+}
+
+/// Split `data` into tag-length-value entries, starting at offset `start`
+/// and stopping at `end_tag` or the end of the data.
+fn entries(addr: u32, data: &[u8], start: usize, end_tag: Option<u8>) -> Vec<TlvEntry> {
+    let mut entries = Vec::new();
+    let mut i = start;
+
+    while let Some(&[tag, len]) = data.get(i..i + 2) {
+        if Some(tag) == end_tag {
+            break;
+        }
+
+        let Some(value) = data.get(i + 2..i + 2 + usize::from(len)) else {
+            break;
+        };
+
+        entries.push(TlvEntry {
+            tag,
+            addr: addr + (i + 2) as u32,
+            value: value.to_vec(),
+        });
+        i += 2 + usize::from(len);
+    }
+
+    entries
+}
+
+/// Check the checksum in the first word of a 2xx segment A, read from `addr`:
+/// the two's complement of the XOR of all other words.
+pub(crate) fn check_segment_a_checksum(addr: u32, data: &[u8]) -> Result<(), Error> {
+    let words = data.len() / 2;
+    let xor = (1..words).fold(0u16, |x, i| x ^ word(data, i));
+
+    check_checksum(addr, xor.wrapping_neg(), word(data, 0))
+}
+
+fn check_checksum(addr: u32, expected: u16, actual: u16) -> Result<(), Error> {
+    if expected != actual {
+        return Err(Error::BadChecksum {
+            addr,
+            expected,
+            actual,
+        });
+    }
+
+    Ok(())
+}
+
+/** CRC-CCITT (polynomial `0x1021`, seed `0xffff`) as computed by the MSP430
+CRC16 module when words are written to `CRCDI`: the low byte first, and each
+byte least significant bit first.
+*/
+pub(crate) fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0xffff, |crc, &b| {
+        (0..8).fold(crc ^ (u16::from(b.reverse_bits()) << 8), |crc, _| {
+            if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
@@ -0,0 +1,95 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// One line of a [`Transcript`](crate::mock::Transcript).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Step {
+    /// A line `mspdebug` prints, sigil included (e.g. `:Device: MSP430G2553`).
+    Output(String),
+    /// A line `mspdebug` expects to read, exactly (e.g. `:reset`).
+    Input(String),
+    /// A line `mspdebug` expects to read, where `*` matches any run of
+    /// characters (e.g. `:prog *.elf`). Only written by hand; recordings
+    /// use [`Step::Input`].
+    #[cfg_attr(not(feature = "mock"), allow(dead_code))]
+    Pattern(String),
+}
+
+/// A [`Step`] of a transcript, with its timestamp if it was recorded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Entry {
+    pub(crate) step: Step,
+    /// Time since the session started, for recorded sessions.
+    pub(crate) time: Option<Duration>,
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(t) = self.time {
+            write!(f, "[{:.6}] ", t.as_secs_f64())?;
+        }
+
+        match &self.step {
+            Step::Output(out) => write!(f, "> {}", out),
+            Step::Input(inp) => write!(f, "< {}", inp),
+            Step::Pattern(pat) => write!(f, "~ {}", pat),
+        }
+    }
+}
+
+/// Writes a timestamped transcript of a session as it happens, so that
+/// nothing is lost if the host crashes.
+pub(crate) struct Recorder {
+    out: BufWriter<File>,
+    start: Instant,
+    /// Input written so far that doesn't end in a newline yet.
+    pending: Vec<u8>,
+}
+
+impl Recorder {
+    pub(crate) fn create<P>(path: P, header: &str) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(out, "# {}", header)?;
+        out.flush()?;
+
+        Ok(Self {
+            out,
+            start: Instant::now(),
+            pending: Vec::new(),
+        })
+    }
+
+    fn record(&mut self, step: Step) {
+        let entry = Entry {
+            step,
+            time: Some(self.start.elapsed()),
+        };
+
+        // A transcript is a debugging aid; failing to write one shouldn't
+        // fail the session being recorded.
+        let _ = writeln!(self.out, "{}", entry).and_then(|_| self.out.flush());
+    }
+
+    /// Record a line read from `mspdebug`.
+    pub(crate) fn output(&mut self, line: &str) {
+        self.record(Step::Output(line.trim_end_matches(['\r', '\n']).to_owned()));
+    }
+
+    /// Record bytes written to `mspdebug`, one step per complete line.
+    pub(crate) fn input(&mut self, buf: &[u8]) {
+        self.pending.extend_from_slice(buf);
+
+        while let Some(i) = self.pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=i).collect();
+            let line = String::from_utf8_lossy(&line);
+
+            self.record(Step::Input(line.trim_end_matches(['\r', '\n']).to_owned()));
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::io;
+use std::net::{Shutdown, TcpStream};
+use std::process::{Child, ChildStderr, ChildStdin, ChildStdout, ExitStatus};
+
+use command_group::GroupChild;
+
+use super::Error;
+
+/** The line-oriented connection to an `mspdebug` running in embedded mode.
+
+[`Cfg::run`](crate::Cfg::run) spawns `mspdebug` and talks to it over pipes,
+but the protocol engine in [`MspDebug`](crate::MspDebug) only needs a pair of
+streams: commands are written to the transport, and output is read from the
+stream returned by [`Transport::take_reader`]. Use
+[`Cfg::open`](crate::Cfg::open) to start a session over any transport.
+
+This crate implements `Transport` for [`TcpStream`], e.g. for `mspdebug`
+started under `socat` on another machine, and for [`Pipe`].
+*/
+pub trait Transport: io::Write + Send {
+    /// Take the stream `mspdebug` prints its output to. Only called once, when
+    /// the session starts; it is then read on a background thread.
+    fn take_reader(&mut self) -> io::Result<Box<dyn io::Read + Send>>;
+
+    /// Take the stream `mspdebug` prints driver messages to, if the transport
+    /// keeps it separate from the output.
+    fn take_stderr(&mut self) -> Option<Box<dyn io::Read + Send>> {
+        None
+    }
+
+    /// Make `mspdebug` go away, e.g. after a command timed out. The reader
+    /// should see EOF soon after.
+    fn kill(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Called when the session ends. Closing the input makes `mspdebug` exit,
+    /// which for most transports happens when they are dropped anyway.
+    fn close(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Wait for `mspdebug` to go away after the reader saw EOF, and return its
+    /// exit status if it has one.
+    fn wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        Ok(None)
+    }
+}
+
+/// The spawned `mspdebug`, which may lead its own process group.
+pub(crate) enum Process {
+    Single(Child),
+    Group(GroupChild),
+}
+
+impl Process {
+    fn inner(&mut self) -> &mut Child {
+        match self {
+            Process::Single(c) => c,
+            Process::Group(g) => g.inner(),
+        }
+    }
+}
+
+/// `mspdebug` spawned by [`Cfg::run`](crate::Cfg::run), talked to over its
+/// stdin and stdout.
+pub(crate) struct ProcessTransport {
+    process: Process,
+    stdin: ChildStdin,
+    stdout: Option<ChildStdout>,
+    stderr: Option<ChildStderr>,
+}
+
+impl ProcessTransport {
+    pub(crate) fn new(mut process: Process) -> Result<Self, Error> {
+        let inner = process.inner();
+        let stdin = inner.stdin.take().ok_or(Error::StreamError("stdin"))?;
+        let stdout = inner.stdout.take().ok_or(Error::StreamError("stdout"))?;
+        let stderr = inner.stderr.take();
+
+        Ok(Self {
+            process,
+            stdin,
+            stdout: Some(stdout),
+            stderr,
+        })
+    }
+}
+
+impl io::Write for ProcessTransport {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.stdin.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stdin.flush()
+    }
+}
+
+impl Transport for ProcessTransport {
+    fn take_reader(&mut self) -> io::Result<Box<dyn io::Read + Send>> {
+        match self.stdout.take() {
+            Some(s) => Ok(Box::new(s)),
+            None => Err(taken()),
+        }
+    }
+
+    fn take_stderr(&mut self) -> Option<Box<dyn io::Read + Send>> {
+        self.stderr
+            .take()
+            .map(|s| Box::new(s) as Box<dyn io::Read + Send>)
+    }
+
+    /// Kill `mspdebug`, and the rest of its process group if it has one.
+    fn kill(&mut self) -> io::Result<()> {
+        match &mut self.process {
+            Process::Single(c) => c.kill(),
+            Process::Group(g) => g.kill(),
+        }
+    }
+
+    fn wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        match &mut self.process {
+            Process::Single(c) => c.wait().map(Some),
+            Process::Group(g) => g.wait().map(Some),
+        }
+    }
+}
+
+/// `mspdebug` on the other end of a TCP connection, e.g. started with
+/// `socat TCP-LISTEN:2001,reuseaddr EXEC:"mspdebug --embedded rf2500 -q"`.
+impl Transport for TcpStream {
+    fn take_reader(&mut self) -> io::Result<Box<dyn io::Read + Send>> {
+        Ok(Box::new(self.try_clone()?))
+    }
+
+    /// Hang up; the remote `mspdebug` is left to notice on its own.
+    fn kill(&mut self) -> io::Result<()> {
+        self.shutdown(Shutdown::Both)
+    }
+
+    // The reader holds a clone of the socket, so dropping this one doesn't
+    // close the connection.
+    fn close(&mut self) -> io::Result<()> {
+        self.shutdown(Shutdown::Write)
+    }
+}
+
+/** A transport over any reader and writer, e.g. the ends of an [`io::pipe`] or
+a serial port connected to a machine running `mspdebug`.
+
+Killing a `Pipe` does nothing; whatever is on the other end has to stop by
+itself when its input is closed.
+*/
+pub struct Pipe<R, W> {
+    reader: Option<R>,
+    writer: W,
+}
+
+impl<R, W> Pipe<R, W> {
+    /// `reader` carries `mspdebug`'s output, and `writer` its input.
+    pub fn new(reader: R, writer: W) -> Self {
+        Self {
+            reader: Some(reader),
+            writer,
+        }
+    }
+}
+
+impl<R, W> io::Write for Pipe<R, W>
+where
+    W: io::Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl<R, W> Transport for Pipe<R, W>
+where
+    R: io::Read + Send + 'static,
+    W: io::Write + Send,
+{
+    fn take_reader(&mut self) -> io::Result<Box<dyn io::Read + Send>> {
+        match self.reader.take() {
+            Some(r) => Ok(Box::new(r)),
+            None => Err(taken()),
+        }
+    }
+}
+
+fn taken() -> io::Error {
+    io::Error::other("transport reader already taken")
+}
@@ -0,0 +1,159 @@
+use std::process::Stdio;
+
+use super::{Cfg, DriverOptions, Error, OutputTail, TargetDriver};
+
+/// USB VID:PID pairs of programmers `mspdebug` has a driver for.
+const KNOWN_PROGRAMMERS: &[(u16, u16, TargetDriver)] = &[
+    (0x0451, 0xf432, TargetDriver::Rf2500),
+    (0x0451, 0xf430, TargetDriver::Uif),
+    (0x2047, 0x0010, TargetDriver::Tilib),
+    (0x2047, 0x0013, TargetDriver::EzFet),
+    (0x2047, 0x0014, TargetDriver::Tilib),
+    (0x2047, 0x0200, TargetDriver::LoadBsl),
+    (0x15ba, 0x0002, TargetDriver::Olimexv1),
+    (0x15ba, 0x0008, TargetDriver::OlimexIso),
+    (0x15ba, 0x0031, TargetDriver::Olimex),
+    (0x15ba, 0x0100, TargetDriver::OlimexIsoMk2),
+];
+
+/// A USB device found by [`list_devices`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UsbDevice {
+    pub bus: u16,
+    pub device: u16,
+    pub vid: u16,
+    pub pid: u16,
+    /// Product name, if `mspdebug` recognizes the device.
+    pub description: String,
+    pub serial: Option<String>,
+    /// Driver to open this device with, if it is a known programmer.
+    pub driver: Option<TargetDriver>,
+}
+
+impl UsbDevice {
+    /** A [`Cfg`] that opens this device, or `None` if it isn't a known
+    programmer.
+
+    The device is selected by serial number if it has one, since that
+    survives being unplugged, and by bus and device number otherwise. Drivers
+    that take neither option (e.g. `tilib`) open whichever device they find.
+    */
+    pub fn cfg(&self) -> Option<Cfg> {
+        let driver = self.driver?;
+        let opts = driver.capabilities().options;
+        let cfg = Cfg::new().driver(driver);
+
+        Some(match &self.serial {
+            Some(s) if opts.contains(DriverOptions::SERIAL) => cfg.serial(s.clone()),
+            _ if opts.contains(DriverOptions::USB_DEVICE) => cfg.usb_device(self.bus, self.device),
+            _ => cfg,
+        })
+    }
+
+    /** Parse the output of `mspdebug --usb-list`.
+
+    ```text
+    Devices on bus 001:
+        001:001 1d6b:0002
+        001:004 0451:f432 eZ430-RF2500 [serial: 2BFF46F5F7F44B16]
+    ```
+    */
+    pub(crate) fn parse_list(output: &str) -> Vec<UsbDevice> {
+        let mut devices: Vec<UsbDevice> = Vec::new();
+
+        for line in output.lines() {
+            // Tolerate embedded-mode sigils.
+            let line = line.strip_prefix(':').unwrap_or(line).trim();
+
+            if let Some(serial) = line
+                .strip_prefix("[serial:")
+                .or_else(|| line.strip_prefix("serial:"))
+            {
+                if let Some(last) = devices.last_mut() {
+                    last.serial = Some(serial.trim_end_matches(']').trim().to_owned());
+                }
+                continue;
+            }
+
+            if let Some(dev) = Self::parse_line(line) {
+                devices.push(dev);
+            }
+        }
+
+        devices
+    }
+
+    fn parse_line(line: &str) -> Option<UsbDevice> {
+        let (loc, rest) = line.split_once(' ')?;
+        let (bus, device) = loc.split_once(':')?;
+        let (bus, device) = (bus.parse().ok()?, device.parse().ok()?);
+
+        let rest = rest.trim_start();
+        let (id, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+        let (vid, pid) = id.split_once(':')?;
+        let vid = u16::from_str_radix(vid, 16).ok()?;
+        let pid = u16::from_str_radix(pid, 16).ok()?;
+
+        let (description, serial) = match rest.split_once("[serial:") {
+            Some((d, s)) => (d, Some(s.trim_end_matches(']').trim().to_owned())),
+            None => (rest, None),
+        };
+        let description = description.trim().to_owned();
+
+        let driver = KNOWN_PROGRAMMERS
+            .iter()
+            .find(|(v, p, _)| *v == vid && *p == pid)
+            .map(|(_, _, d)| *d)
+            // Not in the table above; recognize MehFETs by product name.
+            .or_else(|| {
+                description
+                    .to_ascii_lowercase()
+                    .contains("mehfet")
+                    .then_some(TargetDriver::MehFet)
+            });
+
+        Some(UsbDevice {
+            bus,
+            device,
+            vid,
+            pid,
+            description,
+            serial,
+            driver,
+        })
+    }
+}
+
+impl Cfg {
+    /// List USB devices with `mspdebug --usb-list`, using this configuration's
+    /// `mspdebug` binary. The configured driver is not opened.
+    pub fn list_devices(self) -> Result<Vec<UsbDevice>, Error> {
+        let out = self
+            .usb_list(true)
+            .command()?
+            .stdin(Stdio::null())
+            .output()
+            .map_err(Error::SpawnError)?;
+
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        if !out.status.success() {
+            let stderr = String::from_utf8_lossy(&out.stderr);
+
+            return Err(Error::ChildExited {
+                status: Some(out.status),
+                last_output: OutputTail {
+                    stdout: stdout.lines().map(String::from).collect(),
+                    stderr: stderr.lines().map(String::from).collect(),
+                },
+            });
+        }
+
+        Ok(UsbDevice::parse_list(&stdout))
+    }
+}
+
+/// List USB devices, and the programmers among them, with
+/// `mspdebug --usb-list`. See [`Cfg::list_devices`].
+pub fn list_devices() -> Result<Vec<UsbDevice>, Error> {
+    Cfg::new().list_devices()
+}
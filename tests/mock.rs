@@ -0,0 +1,499 @@
+#![cfg(feature = "mock")]
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+
+use mspdebug_embedded::mock::{Transcript, TRANSCRIPT_VAR};
+use mspdebug_embedded::{
+    BadInputReason, Cfg, Error, FitReason, GdbCfg, ProgramOptions, Severity, TargetDriver,
+};
+
+fn temp_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("mspdebug-mock-{}-{}", process::id(), name))
+}
+
+fn mock(name: &str, transcript: Transcript) -> Cfg {
+    let path = temp_path(&format!("{}.txt", name));
+    transcript.save(&path).unwrap();
+
+    Cfg::new()
+        .binary(env!("CARGO_BIN_EXE_mspdebug-mock"))
+        .env(TRANSCRIPT_VAR, path)
+}
+
+fn rf2500_startup() -> Transcript {
+    Transcript::new()
+        .output(":Initializing FET...")
+        .output(":Configured for Spy-Bi-Wire")
+        .output(":Device: MSP430G2xx3")
+        .ready()
+}
+
+#[test]
+fn test_transcript_roundtrip() {
+    let t = rf2500_startup().command("md 0x200 2", &["    00200: ff ff |..|"]);
+
+    assert_eq!(Transcript::parse(&t.to_string()).unwrap(), t);
+    assert!(Transcript::parse("? :reset").is_err());
+}
+
+#[test]
+fn test_read_memory() {
+    let t = Transcript::new()
+        .ready()
+        .command("md 0x200 4", &["    00200: de ad be ef |....|"]);
+    let mut msp = mock("read_memory", t).run().unwrap();
+
+    assert_eq!(msp.read_memory(0x200, 4).unwrap(), [0xde, 0xad, 0xbe, 0xef]);
+}
+
+#[test]
+fn test_diagnostics() {
+    let t = Transcript::new()
+        .ready()
+        .expect(":reset")
+        .busy()
+        .output("!warning: device does not support power profiling")
+        .output("!fet: FET returned NAK")
+        .ready();
+    let mut msp = mock("diagnostics", t).run().unwrap();
+
+    msp.reset().unwrap();
+    let diags = msp.drain_diagnostics();
+    assert_eq!(diags.len(), 2);
+    assert_eq!(diags[0].severity, Severity::Warning);
+    assert_eq!(diags[1].severity, Severity::IgnoredError);
+}
+
+#[test]
+fn test_comms_error() {
+    let t = Transcript::new()
+        .ready()
+        .expect(":erase")
+        .busy()
+        .output("!fet: failed to erase")
+        .ready();
+    let mut msp = mock("comms_error", t).run().unwrap();
+
+    assert!(matches!(
+        msp.erase(),
+        Err(Error::CommsError { msg, .. }) if msg == "fet: failed to erase"
+    ));
+}
+
+#[test]
+fn test_unexpected_command() {
+    let t = Transcript::new().ready().command("reset", &[]);
+    let mut msp = mock("unexpected_command", t).run().unwrap();
+
+    assert!(matches!(
+        msp.erase(),
+        Err(Error::CommsError { msg, .. }) if msg.starts_with("transcript: expected \":reset\"")
+    ));
+}
+
+#[test]
+fn test_program() {
+    let t = rf2500_startup().command_matching("prog *blinky-g2553.elf", &[]);
+    let mut msp = mock("program", t)
+        .driver(TargetDriver::Rf2500)
+        .run()
+        .unwrap();
+
+    msp.program("assets/blinky-g2553.elf").unwrap();
+    assert_eq!(msp.device(), Some("MSP430G2xx3"));
+}
+
+#[test]
+fn test_program_verify() {
+    // blinky's sections, as (load address, file offset, size). The first
+    // loadable segment only holds the ELF headers and .bss, and is never
+    // read back.
+    let elf = fs::read("assets/blinky-g2553.elf").unwrap();
+    let sections = [
+        (0xffe0, 0x160, 0x20),
+        (0xc000, 0x94, 0x9c),
+        (0xc09c, 0x130, 0x30),
+    ];
+
+    let transcript = |corrupt: Option<u32>| {
+        let t = rf2500_startup().command_matching("prog *blinky-g2553.elf", &[]);
+
+        sections.iter().fold(t, |t, &(addr, offset, size)| {
+            let mut data = elf[offset..offset + size].to_vec();
+            if let Some(c) = corrupt.filter(|&c| (addr..addr + size as u32).contains(&c)) {
+                data[(c - addr) as usize] ^= 0xff;
+            }
+
+            let dump = hexdump(addr, &data);
+            let dump: Vec<&str> = dump.iter().map(String::as_str).collect();
+            t.command(&format!("md 0x{:x} {}", addr, size), &dump)
+        })
+    };
+
+    let mut msp = mock("program_verify", transcript(None))
+        .driver(TargetDriver::Rf2500)
+        .run()
+        .unwrap();
+    msp.program_with(
+        "assets/blinky-g2553.elf",
+        ProgramOptions::default().verify(),
+    )
+    .unwrap();
+    drop(msp);
+
+    // One byte of .rodata didn't take.
+    let mut msp = mock("program_verify_corrupt", transcript(Some(0xc0a0)))
+        .driver(TargetDriver::Rf2500)
+        .run()
+        .unwrap();
+    match msp.program_with(
+        "assets/blinky-g2553.elf",
+        ProgramOptions::default().verify(),
+    ) {
+        Err(Error::VerifyFailed {
+            addr,
+            expected,
+            actual,
+        }) => {
+            assert_eq!(addr, 0xc0a0);
+            assert_eq!(actual, !expected);
+        }
+        res => panic!("expected VerifyFailed, got {:?}", res),
+    }
+}
+
+#[test]
+fn test_program_infomem() {
+    // Load blinky's vector table at 0x1030, across Information Memory
+    // segments D and C.
+    let mut elf = fs::read("assets/blinky-g2553.elf").unwrap();
+    let phoff = u32::from_le_bytes(elf[28..32].try_into().unwrap()) as usize;
+    let phentsize = u16::from_le_bytes(elf[42..44].try_into().unwrap()) as usize;
+    let vectors_paddr = phoff + 2 * phentsize + 12;
+    elf[vectors_paddr..vectors_paddr + 4].copy_from_slice(&0x1030u32.to_le_bytes());
+
+    let path = temp_path("infomem.elf");
+    fs::write(&path, elf).unwrap();
+
+    // Only the segments the image touches; B is left alone.
+    let t = rf2500_startup()
+        .command("erase segrange 4096 128 64", &[])
+        .command_matching("prog *infomem.elf", &[]);
+    let mut msp = mock("program_infomem", t)
+        .driver(TargetDriver::Rf2500)
+        .run()
+        .unwrap();
+
+    msp.program(&path).unwrap();
+    drop(msp);
+
+    // The first loadable segment holds only headers and .bss; mspdebug
+    // doesn't program it, so Information Memory is left alone.
+    let mut elf = fs::read("assets/blinky-g2553.elf").unwrap();
+    let headers_paddr = phoff + 12;
+    elf[headers_paddr..headers_paddr + 4].copy_from_slice(&0x1000u32.to_le_bytes());
+
+    let path = temp_path("headers.elf");
+    fs::write(&path, elf).unwrap();
+
+    let t = rf2500_startup().command_matching("prog *headers.elf", &[]);
+    let mut msp = mock("program_headers", t)
+        .driver(TargetDriver::Rf2500)
+        .run()
+        .unwrap();
+
+    msp.program(&path).unwrap();
+}
+
+#[test]
+fn test_program_single_infomem_segment() {
+    // The L092's Information Memory is segment A alone.
+    let t = Transcript::new()
+        .output(":Device: MSP430L092")
+        .ready()
+        .command_matching("prog *blinky-g2553.elf", &[]);
+    let mut msp = mock("program_single_infomem_segment", t)
+        .driver(TargetDriver::Rf2500)
+        .run()
+        .unwrap();
+
+    msp.program("assets/blinky-g2553.elf").unwrap();
+}
+
+#[test]
+fn test_program_wrong_variant() {
+    // Load blinky's code at 0x8000, as if built for a 32 KiB part.
+    let mut elf = fs::read("assets/blinky-g2553.elf").unwrap();
+    let phoff = u32::from_le_bytes(elf[28..32].try_into().unwrap()) as usize;
+    let phentsize = u16::from_le_bytes(elf[42..44].try_into().unwrap()) as usize;
+    let text_paddr = phoff + phentsize + 12;
+    elf[text_paddr..text_paddr + 4].copy_from_slice(&0x8000u32.to_le_bytes());
+
+    let path = temp_path("wrong_variant.elf");
+    fs::write(&path, &elf).unwrap();
+
+    let t = rf2500_startup().command_matching("prog *wrong_variant.elf", &[]);
+    let mut msp = mock("program_wrong_variant", t)
+        .driver(TargetDriver::Rf2500)
+        .run()
+        .unwrap();
+
+    assert!(matches!(
+        msp.program(&path),
+        Err(Error::BadInput(BadInputReason::DoesNotFit {
+            section,
+            addr: 0x8000,
+            reason: FitReason::TooLarge,
+            ..
+        })) if section == ".text"
+    ));
+
+    msp.program_with(&path, ProgramOptions::default().allow_misfit())
+        .unwrap();
+    let diags = msp.drain_diagnostics();
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].severity, Severity::Warning);
+    assert!(diags[0]
+        .message
+        .starts_with("section .text at 0x8000 does not fit"));
+
+    // Not an MSP430 file at all.
+    elf[18..20].copy_from_slice(&62u16.to_le_bytes());
+    fs::write(&path, &elf).unwrap();
+
+    assert!(matches!(
+        msp.program(&path),
+        Err(Error::BadInput(BadInputReason::WrongMachine(62)))
+    ));
+}
+
+#[test]
+fn test_program_unknown_map() {
+    let t = Transcript::new()
+        .output(":Device: MSP430F5341")
+        .ready()
+        .command_matching("prog *blinky-g2553.elf", &[]);
+    let mut msp = mock("program_unknown_map", t)
+        .driver(TargetDriver::Tilib)
+        .run()
+        .unwrap();
+
+    msp.program("assets/blinky-g2553.elf").unwrap();
+    assert_eq!(
+        msp.drain_diagnostics()[0].message,
+        "no memory map for MSP430F5341; not checking that the ELF file fits"
+    );
+}
+
+#[tokio::test]
+#[cfg(feature = "tokio")]
+async fn test_async_read_memory() {
+    let t = Transcript::new()
+        .ready()
+        .command("md 0x200 4", &["    00200: de ad be ef |....|"]);
+    let mut msp = mock("async_read_memory", t).run_async().await.unwrap();
+
+    let mem = msp.read_memory(0x200, 4).await.unwrap();
+    assert_eq!(mem, [0xde, 0xad, 0xbe, 0xef]);
+}
+
+#[tokio::test]
+#[cfg(feature = "tokio")]
+async fn test_async_process_group() {
+    let t = rf2500_startup();
+    let mut msp = mock("async_group_program", t)
+        .group(true)
+        .run_async()
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        msp.program("assets/blinky-g2553.elf").await,
+        Err(Error::ExpectedNoProcessGroup)
+    ));
+
+    let t = rf2500_startup();
+    let msp = mock("async_group_gdb", t).run_async().await.unwrap();
+
+    assert!(matches!(
+        msp.gdb("assets/blinky-g2553.elf", GdbCfg::default()).await,
+        Err(Error::ExpectedProcessGroup)
+    ));
+}
+
+#[test]
+fn test_timeout() {
+    // mspdebug starts erasing but never finishes.
+    let t = Transcript::new().ready().expect(":erase").busy();
+    let mut msp = mock("timeout", t)
+        .timeout(Duration::from_millis(100))
+        .run()
+        .unwrap();
+
+    match msp.erase() {
+        Err(Error::Timeout(t)) => assert_eq!(t, Duration::from_millis(100)),
+        res => panic!("expected Timeout, got {:?}", res),
+    }
+
+    // Timing out killed mspdebug.
+    assert!(matches!(msp.reset(), Err(Error::ChildExited { .. })));
+}
+
+#[tokio::test]
+#[cfg(feature = "tokio")]
+async fn test_async_timeout() {
+    let t = Transcript::new().ready().expect(":erase").busy();
+    let mut msp = mock("async_timeout", t)
+        .timeout(Duration::from_millis(100))
+        .run_async()
+        .await
+        .unwrap();
+
+    assert!(matches!(msp.erase().await, Err(Error::Timeout(_))));
+    assert!(matches!(msp.reset().await, Err(Error::ChildExited { .. })));
+}
+
+#[tokio::test]
+#[cfg(feature = "tokio")]
+async fn test_async_cancel() {
+    // mspdebug starts erasing but never finishes.
+    let t = Transcript::new().ready().expect(":erase").busy();
+    let mut msp = mock("async_cancel", t).run_async().await.unwrap();
+
+    let erase = tokio::time::timeout(Duration::from_millis(100), msp.erase()).await;
+    assert!(erase.is_err());
+
+    // Cancelling the erase killed mspdebug.
+    assert!(matches!(msp.reset().await, Err(Error::ChildExited { .. })));
+}
+
+#[tokio::test]
+#[cfg(feature = "tokio")]
+async fn test_async_profile_power() {
+    // The target hits a breakpoint before the recording ends.
+    let t = Transcript::new()
+        .ready()
+        .expect(":run")
+        .busy()
+        .output("\\power-sample-us 500")
+        .output("\\power-samples AMAAADgBAAAEwAAAKgEAAA==")
+        .output(":    ( PC: 0c004)  ( R4: 00000)  ( R8: 00000)  (R12: 00000)")
+        .output(":    ( SP: 003fe)  ( R5: 00000)  ( R9: 00000)  (R13: 00000)")
+        .output(":    ( SR: 00000)  ( R6: 00000)  (R10: 00000)  (R14: 00000)")
+        .output(":    ( R3: 00000)  ( R7: 00000)  (R11: 00000)  (R15: 00000)")
+        .ready()
+        .command(
+            "regs",
+            &[
+                "    ( PC: 0c004)  ( R4: 00000)  ( R8: 00000)  (R12: 00000)",
+                "    ( SP: 003fe)  ( R5: 00000)  ( R9: 00000)  (R13: 00000)",
+                "    ( SR: 00000)  ( R6: 00000)  (R10: 00000)  (R14: 00000)",
+                "    ( R3: 00000)  ( R7: 00000)  (R11: 00000)  (R15: 00000)",
+            ],
+        )
+        .command("break", &["2 breakpoints available:"]);
+    let mut msp = mock("async_profile_power", t)
+        .driver(TargetDriver::EzFet)
+        .run_async()
+        .await
+        .unwrap();
+
+    let profile = msp.profile_power(Duration::from_secs(10)).await.unwrap();
+    assert_eq!(profile.samples().len(), 2);
+    assert_eq!(profile.duration(), Duration::from_millis(1));
+}
+
+fn hexdump(addr: u32, data: &[u8]) -> Vec<String> {
+    data.chunks(16)
+        .enumerate()
+        .map(|(i, line)| {
+            let bytes: Vec<_> = line.iter().map(|b| format!("{:02x}", b)).collect();
+            format!("    {:05x}: {} |", addr as usize + i * 16, bytes.join(" "))
+        })
+        .collect()
+}
+
+#[test]
+fn test_protect_calibration() {
+    // Segment A starts with the negated XOR of its other words.
+    let mut cal: Vec<u8> = (0..64).collect();
+    let xor = cal[2..]
+        .chunks(2)
+        .fold(0u16, |x, w| x ^ u16::from_le_bytes([w[0], w[1]]));
+    cal[..2].copy_from_slice(&xor.wrapping_neg().to_le_bytes());
+
+    let mw = cal.iter().fold("mw 0x10c0".to_owned(), |cmd, b| {
+        format!("{} 0x{:02x}", cmd, b)
+    });
+    let dump = hexdump(0x10c0, &cal);
+    let dump: Vec<&str> = dump.iter().map(String::as_str).collect();
+    let erased = hexdump(0x10c0, &[0xff; 64]);
+    let erased: Vec<&str> = erased.iter().map(String::as_str).collect();
+
+    // This erase takes segment A with it.
+    let t = rf2500_startup()
+        .command("md 0x10c0 64", &dump)
+        .command("erase", &[])
+        .command("md 0x10c0 64", &erased)
+        .command("erase segrange 4288 64 64", &[])
+        .command(&mw, &[]);
+    let dir = temp_path("calibration");
+    let mut msp = mock("protect_calibration", t)
+        .driver(TargetDriver::Rf2500)
+        .serial("0123456789ABCDEF")
+        .calibration_backups(&dir)
+        .run()
+        .unwrap();
+
+    msp.erase().unwrap();
+    drop(msp);
+    let backup = dir.join("MSP430G2xx3-0123456789ABCDEF.infoa");
+    assert!(backup.exists());
+
+    // A later session restores the backup.
+    let t = rf2500_startup()
+        .command("erase segrange 4288 64 64", &[])
+        .command(&mw, &[]);
+    let mut msp = mock("restore_calibration", t)
+        .driver(TargetDriver::Rf2500)
+        .serial("0123456789ABCDEF")
+        .calibration_backups(&dir)
+        .run()
+        .unwrap();
+
+    msp.restore_segment_a().unwrap();
+    drop(msp);
+
+    // Without a serial number, there is no telling which board a backup
+    // came from.
+    let mut msp = mock("restore_no_serial", rf2500_startup())
+        .driver(TargetDriver::Rf2500)
+        .calibration_backups(&dir)
+        .run()
+        .unwrap();
+
+    assert!(matches!(
+        msp.restore_segment_a(),
+        Err(Error::CalibrationBackupError(_))
+    ));
+    drop(msp);
+
+    // A damaged backup is never written.
+    let text = fs::read_to_string(&backup).unwrap();
+    fs::write(&backup, text.replacen(" 06 07", " 06 17", 1)).unwrap();
+    let mut msp = mock("restore_corrupt", rf2500_startup())
+        .driver(TargetDriver::Rf2500)
+        .serial("0123456789ABCDEF")
+        .calibration_backups(&dir)
+        .run()
+        .unwrap();
+
+    assert!(matches!(
+        msp.restore_segment_a(),
+        Err(Error::BadChecksum { addr: 0x10c0, .. })
+    ));
+}